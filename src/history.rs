@@ -0,0 +1,95 @@
+use crate::sudoku::{Coloring, SolveStep, SudokuSquare};
+use crate::App;
+
+// How many changes are kept to undo before the oldest is forgotten
+const MAX_HISTORY: usize = 100;
+
+// The state of the board as it was before a change, to bring back on undo
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    grid: Vec<Vec<SudokuSquare>>,
+    solved: bool,
+    options_ready: bool,
+    coloring: Option<Coloring>,
+    solve_path: Vec<SolveStep>,
+}
+
+// The changes which can be undone, and the undone changes which can be redone
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl App {
+    // Take a copy of the board to restore later
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            grid: self.sudoku.grid.clone(),
+            solved: self.sudoku.solved,
+            options_ready: self.sudoku.options_ready,
+            coloring: self.sudoku.coloring.clone(),
+            solve_path: self.solve_path.clone(),
+        }
+    }
+
+    // Remember the board from before a change so it can be undone, unless nothing actually changed
+    // A new change means the undone ones can't be redone any more
+    pub fn record_change(&mut self, before: Snapshot) {
+        if before.grid == self.sudoku.grid && before.solve_path.len() == self.solve_path.len() {
+            return;
+        }
+        self.history.undo.push(before);
+        if self.history.undo.len() > MAX_HISTORY {
+            self.history.undo.remove(0);
+        }
+        self.history.redo.clear();
+    }
+
+    // Go back to the board from before the last change
+    pub fn undo(&mut self) {
+        match self.history.undo.pop() {
+            Some(before) => {
+                let after: Snapshot = self.restore(before);
+                self.history.redo.push(after);
+                self.sudoku.status = "Undid the last change".to_string();
+            }
+            None => self.sudoku.status = "Nothing to undo".to_string(),
+        }
+    }
+
+    // Make the last undone change again
+    pub fn redo(&mut self) {
+        match self.history.redo.pop() {
+            Some(after) => {
+                let before: Snapshot = self.restore(after);
+                self.history.undo.push(before);
+                self.sudoku.status = "Redid the last undone change".to_string();
+            }
+            None => self.sudoku.status = "Nothing to redo".to_string(),
+        }
+    }
+
+    // Put the board back as it was in the snapshot, returning the board it replaced
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let replaced: Snapshot = self.snapshot();
+        self.sudoku.grid = snapshot.grid;
+        self.sudoku.solved = snapshot.solved;
+        self.sudoku.options_ready = snapshot.options_ready;
+        self.sudoku.coloring = snapshot.coloring;
+        self.solve_path = snapshot.solve_path;
+        // The grid may have different givens, so whether it has a unique solution is checked again
+        self.sudoku.unique.set(None);
+        replaced
+    }
+}
@@ -1,17 +1,26 @@
+mod history;
 mod sudoku;
-use sudoku::{vec_to_string, Sudoku, SudokuSquare};
+use history::History;
+use sudoku::{
+    random_seed, vec_to_string, Coloring, Difficulty, GeneratorSettings, SolveStep, SolverSettings, Sudoku, SudokuSquare,
+    Symmetry,
+};
 
-use iced::widget::{button, column, container, row, text, text_input};
+use iced::widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input};
 use iced::widget::{Column, Container, Row, Text};
-use iced::{alignment, border};
-use iced::{Application, Border, Color, Element, Settings, Theme};
+use iced::alignment;
+use iced::event::{self, Event};
+use iced::keyboard::{self, Key};
+use iced::{clipboard, window, Color, Subscription, Task, Theme};
 use regex::{Regex};
+use std::path::Path;
 
 pub fn main() -> iced::Result {
-    iced::application("Sudoku Solver", Sudoku::update, Sudoku::view)
+    iced::application("Sudoku Solver", App::update, App::view)
         .theme(|_| Theme::Dark)
+        .subscription(App::subscription)
         .centered()
-        .run()
+        .run_with(App::new)
 }
 
 #[derive(Debug, Clone)]
@@ -23,26 +32,106 @@ enum Message {
     Solve,
     SolveNext,
     Check,
+    Verify,
+    Rate,
+    Undo,
+    Redo,
+    NewPuzzle,
+    PuzzleGenerated(u64, Option<Box<Sudoku>>), // (Seed, Puzzle)
+    NewPuzzleDifficulty(Difficulty),
+    NewPuzzleSymmetry(Symmetry),
+    NewPuzzleMinimal(bool),
+    FilePathChanged(String),
+    Open,
+    Save,
+    CopyPuzzle,
+    CopyPencilMarks,
+    PastePuzzle,
+    PuzzlePasted(Option<String>),
     Options,
+    AssumeUnique(bool),
+    SinglesOnly(bool),
+    TechniqueEnabled(&'static str, bool),
 }
 
-impl Sudoku {
-    pub fn update(&mut self, message: Message) {
+// The state of the application: the Sudoku being solved, and what the GUI keeps alongside it, so
+// the solver's copies of the Sudoku don't carry it around
+#[derive(Debug)]
+struct App {
+    sudoku: Sudoku,
+    // The steps taken by the solver since the grid was last reset or cleared
+    solve_path: Vec<SolveStep>,
+    // The options for the next generated puzzle
+    generator: GeneratorSettings,
+    // Whether a puzzle is being generated in the background
+    generating: bool,
+    // Earlier boards to go back to after edits and solver actions
+    history: History,
+    // The file which Open and Save use
+    file_path: String,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App {
+            sudoku: Sudoku::default(),
+            solve_path: vec![],
+            generator: GeneratorSettings::default(),
+            generating: false,
+            history: History::default(),
+            file_path: "sudoku.txt".to_string(),
+        }
+    }
+}
+
+impl App {
+    // Start on an empty board while the first puzzle is generated
+    fn new() -> (App, Task<Message>) {
+        let mut app: App = App::default();
+        let task: Task<Message> = app.generate_puzzle();
+        (app, task)
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        // Keep the board from before any change to it, so the change can be undone
+        let before = match message {
+            Message::TextChanged(..)
+            | Message::Reset
+            | Message::Lock
+            | Message::Clear
+            | Message::Solve
+            | Message::SolveNext
+            | Message::PuzzleGenerated(..)
+            | Message::Open
+            | Message::PuzzlePasted(_) => Some(self.snapshot()),
+            _ => None,
+        };
+        // Work which has to wait for the runtime, like reading the clipboard
+        let mut task: Task<Message> = Task::none();
+
         match message {
             Message::TextChanged(id, input) => {
-                self.square_text_update(&id, &input);
+                match pasted_puzzle(&self.sudoku.grid, &id, &input) {
+                    Some(puzzle) => {
+                        self.replace_grid(puzzle);
+                        self.sudoku.status = "Pasted the puzzle into the board".to_string();
+                    }
+                    None => self.sudoku.square_text_update(&id, &input),
+                }
             }
             Message::Reset => {
-                self.grid = vec![vec![SudokuSquare::default(); 9]; 9];
-                self.solved = false;
-                self.status = String::default();
+                self.sudoku.grid = vec![vec![SudokuSquare::default(); 9]; 9];
+                self.sudoku.solved = false;
+                self.sudoku.status = String::default();
+                self.solve_path.clear();
+                self.sudoku.grid_edited();
             }
             Message::Lock => {
                 // Lock the given squares
                 for c in 0..9 {
                     for r in 0..9 {
-                        if self.grid[c][r].value.is_some() {
-                            self.grid[c][r].given = true;
+                        if self.sudoku.grid[c][r].value.is_some() {
+                            self.sudoku.grid[c][r].given = true;
                         }
                     }
                 }
@@ -51,43 +140,162 @@ impl Sudoku {
                 // Clear all the edited squares, leaving the given ones
                 for c in 0..9 {
                     for r in 0..9 {
-                        if !self.grid[c][r].given {
-                            self.grid[c][r].value = None;
-                            self.grid[c][r].given = false;
+                        if !self.sudoku.grid[c][r].given {
+                            self.sudoku.grid[c][r].value = None;
+                            self.sudoku.grid[c][r].given = false;
                         }
                     }
                 }
-                self.solved = false;
-                self.status = String::default();
+                self.sudoku.solved = false;
+                self.sudoku.status = String::default();
+                self.solve_path.clear();
+                self.sudoku.grid_edited();
             }
             Message::Solve => {
                 // Solve the Sudoku puzzle
-                self.solve(false);
+                let steps: Vec<SolveStep> = self.sudoku.solve(false);
+                self.solve_path.extend(steps);
             }
             Message::SolveNext => {
                 // Solve the Sudoku puzzle
-                self.solve(true);
+                let steps: Vec<SolveStep> = self.sudoku.solve(true);
+                self.solve_path.extend(steps);
             }
             Message::Check => {
-                self.check_solved();
+                self.sudoku.check_solved();
             }
-            Message::Options => {
-                for r in 0..9 {
-                    for c in 0..9 {
-                        if self.grid[r][c].value.is_none() {
-                            self.update_options(r, c);
-                        }
+            Message::Verify => {
+                self.sudoku.verify_puzzle();
+            }
+            Message::Rate => {
+                self.sudoku.rate_puzzle();
+            }
+            Message::Undo => {
+                self.undo();
+            }
+            Message::Redo => {
+                self.redo();
+            }
+            Message::NewPuzzle => {
+                task = self.generate_puzzle();
+            }
+            Message::PuzzleGenerated(seed, puzzle) => {
+                // Keep the settings, swapping in the new grid
+                self.generating = false;
+                match puzzle {
+                    Some(puzzle) => {
+                        self.replace_grid(*puzzle);
+                        self.sudoku.status = format!("New {} puzzle from seed {}", self.generator.difficulty, seed);
                     }
+                    None => {
+                        self.sudoku.status = format!("Unable to generate a {} puzzle with these options", self.generator.difficulty);
+                    }
+                }
+            }
+            Message::NewPuzzleDifficulty(difficulty) => {
+                self.generator.difficulty = difficulty;
+            }
+            Message::NewPuzzleSymmetry(symmetry) => {
+                self.generator.symmetry = symmetry;
+            }
+            Message::NewPuzzleMinimal(minimal) => {
+                self.generator.minimal = minimal;
+            }
+            Message::FilePathChanged(path) => {
+                self.file_path = path;
+            }
+            Message::Open => {
+                match Sudoku::load(Path::new(&self.file_path)) {
+                    Ok(loaded) => {
+                        self.replace_grid(loaded);
+                        self.sudoku.status = format!("Opened {}", self.file_path);
+                    }
+                    Err(error) => {
+                        self.sudoku.status = format!("Unable to open {}: {}", self.file_path, error);
+                    }
+                }
+            }
+            Message::Save => {
+                self.sudoku.status = match self.sudoku.save(Path::new(&self.file_path)) {
+                    Ok(()) => format!("Saved {}", self.file_path),
+                    Err(error) => format!("Unable to save {}: {}", self.file_path, error),
+                };
+            }
+            Message::CopyPuzzle => {
+                task = clipboard::write(self.sudoku.to_line());
+                self.sudoku.status = "Copied the puzzle to the clipboard".to_string();
+            }
+            Message::CopyPencilMarks => {
+                task = clipboard::write(self.sudoku.to_pencil_marks());
+                self.sudoku.status = "Copied the pencil marks to the clipboard".to_string();
+            }
+            Message::PastePuzzle => {
+                task = clipboard::read().map(Message::PuzzlePasted);
+            }
+            Message::PuzzlePasted(contents) => {
+                self.sudoku.status = match Sudoku::from_text(contents.as_deref().unwrap_or_default()) {
+                    Ok(puzzle) => {
+                        self.replace_grid(puzzle);
+                        "Pasted the puzzle from the clipboard".to_string()
+                    }
+                    Err(error) => format!("Unable to paste the puzzle: {}", error),
+                };
+            }
+            Message::AssumeUnique(assume_unique) => {
+                self.sudoku.settings.assume_unique = assume_unique;
+            }
+            Message::SinglesOnly(singles_only) => {
+                self.sudoku.settings.set_singles_only(singles_only);
+            }
+            Message::TechniqueEnabled(name, enabled) => {
+                self.sudoku.settings.set_enabled(name, enabled);
+            }
+            Message::Options => {
+                // Keep any options already narrowed down by the solver
+                if !self.sudoku.options_ready {
+                    self.sudoku.update_all_options();
                 }
             }
         }
+
+        if let Some(before) = before {
+            self.record_change(before);
+        }
+        task
+    }
+
+    // Generate a puzzle with the current generator options. Generating can take a few seconds, so it
+    // runs off the UI thread and the puzzle arrives as a PuzzleGenerated message
+    fn generate_puzzle(&mut self) -> Task<Message> {
+        let seed: u64 = random_seed();
+        let settings: GeneratorSettings = self.generator.clone();
+        self.generating = true;
+        self.sudoku.status = format!("Generating a {} puzzle...", settings.difficulty);
+        Task::perform(async move { Sudoku::generate(seed, &settings) }, move |puzzle| {
+            Message::PuzzleGenerated(seed, puzzle.map(Box::new))
+        })
+    }
+
+    // Swap in the grid from another puzzle, keeping the settings
+    fn replace_grid(&mut self, puzzle: Sudoku) {
+        self.sudoku.grid = puzzle.grid;
+        self.sudoku.options_ready = puzzle.options_ready;
+        self.sudoku.unique.set(None);
+        self.sudoku.solved = false;
+        self.sudoku.coloring = None;
+        self.solve_path.clear();
+    }
+
+    // Listen for the undo and redo keyboard shortcuts, even while a square is being edited
+    pub fn subscription(&self) -> Subscription<Message> {
+        event::listen_with(shortcut)
     }
 
     // Define the view of the application
-    pub fn view(&self) -> Column<Message> {
+    pub fn view(&self) -> Column<'_, Message> {
         // Create widget from Sudoku grid
-        let self_grid_widget: Container<'_, Message> = create_grid_widget(&self.grid);
-        let self_options_widget: Container<'_, Message> = create_options_widget(&self.grid);
+        let self_grid_widget: Container<'_, Message> = create_grid_widget(&self.sudoku.grid, &self.sudoku.conflicting_squares());
+        let self_options_widget: Container<'_, Message> = create_options_widget(&self.sudoku.grid, self.sudoku.coloring.as_ref());
 
         column![
             text("Welcome to the Sudoku Solver!").size(30),
@@ -99,18 +307,46 @@ impl Sudoku {
                 Container::new(button("Solve Next").on_press(Message::SolveNext).padding(5)).padding(3),
                 Container::new(button("Solve!").on_press(Message::Solve).padding(5)).padding(3),
                 Container::new(button("Check").on_press(Message::Check).padding(5)).padding(3),
+                Container::new(button("Verify").on_press(Message::Verify).padding(5)).padding(3),
+                Container::new(button("Rate").on_press(Message::Rate).padding(5)).padding(3),
+                Container::new(button("Undo").on_press_maybe(self.history.can_undo().then_some(Message::Undo)).padding(5)).padding(3),
+                Container::new(button("Redo").on_press_maybe(self.history.can_redo().then_some(Message::Redo)).padding(5)).padding(3),
                 ],
+            row![
+                Container::new(button("New Puzzle").on_press_maybe((!self.generating).then_some(Message::NewPuzzle)).padding(5)).padding(3),
+                Container::new(pick_list(Difficulty::ALL, Some(self.generator.difficulty), Message::NewPuzzleDifficulty)).padding(3),
+                Container::new(pick_list(Symmetry::ALL, Some(self.generator.symmetry), Message::NewPuzzleSymmetry)).padding(3),
+                Container::new(checkbox("Minimal", self.generator.minimal).on_toggle(Message::NewPuzzleMinimal)).padding(3),
+            ]
+            .align_y(alignment::Vertical::Center),
+            row![
+                Container::new(text_input("File", &self.file_path).on_input(Message::FilePathChanged).padding(5).width(300)).padding(3),
+                Container::new(button("Open").on_press(Message::Open).padding(5)).padding(3),
+                Container::new(button("Save").on_press(Message::Save).padding(5)).padding(3),
+                Container::new(button("Copy Puzzle").on_press(Message::CopyPuzzle).padding(5)).padding(3),
+                Container::new(button("Copy Pencil Marks").on_press(Message::CopyPencilMarks).padding(5)).padding(3),
+                Container::new(button("Paste Puzzle").on_press(Message::PastePuzzle).padding(5)).padding(3),
+            ]
+            .align_y(alignment::Vertical::Center),
             row! [self_grid_widget, text("        "), self_options_widget],
+            row![
+                Container::new(checkbox("Assume a unique solution", self.sudoku.settings.assume_unique).on_toggle(Message::AssumeUnique)).padding(3),
+                Container::new(checkbox("Singles only", self.sudoku.settings.is_singles_only()).on_toggle(Message::SinglesOnly)).padding(3),
+            ],
+            create_techniques_widget(&self.sudoku.settings),
             // self_grid_widget,
-            text(self.status.clone()).size(20),
+            text(self.sudoku.status.clone()).size(20),
+            create_solve_path_widget(&self.solve_path),
         ]
     }
+}
 
+impl Sudoku {
     pub fn square_text_update(&mut self, id: &str, input: &str) {
         // Parse ID into row and column indices
         let c: usize;
         let r: usize;
-        (c, r) = match parse_square_id(&id) {
+        (c, r) = match parse_square_id(id) {
             Some((c, r)) => (c, r),
             None => return,
         };
@@ -122,22 +358,28 @@ impl Sudoku {
         };
 
         // Update square value with new input
-        let new_text = get_new_square_text(&prev_text, &input);
-        if new_text.len() > 0 {
+        // Hand edits can bring options back, so they are listed again from scratch on the next solve
+        self.grid_edited();
+        let new_text = get_new_square_text(&prev_text, input);
+        if !new_text.is_empty() {
             self.set_square(c, r, new_text.parse::<u32>().unwrap(), false);
         } else {
             self.grid[c][r].value = None;
         }
+
+        // Say straight away which unit the new value clashes with
+        self.status = self.conflict_at(c, r).unwrap_or_default();
     }
 
     // Set the grid's square to the given value
     pub fn set_square(&mut self, r: usize, c: usize, val: u32, given: bool) {
-        if val >= 1 && val <= 9 {
+        if (1..=9).contains(&val) {
             self.grid[r][c] = SudokuSquare {
                 value: Some(val),
                 options: Vec::default(),
-                given: given,
+                given,
             };
+            self.remove_peer_options(r, c, val);
             self.solved = false;
             self.status = String::default();
         }
@@ -147,7 +389,7 @@ impl Sudoku {
 // ---------------------------- Helper functions ----------------------------
 
 // Convers Sudoku grid to Iced Column widget
-fn create_grid_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Message, Theme> {
+fn create_grid_widget(grid: &[Vec<SudokuSquare>], conflicts: &[(usize, usize)]) -> Container<'static, Message, Theme> {
     // Create a column widget to hold the rows
     let mut column = Column::new();
 
@@ -179,6 +421,9 @@ fn create_grid_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Messa
                         None => "".to_string(),
                     };
 
+                    // Warn about a value which is repeated in its row, column, or box
+                    let conflict: bool = conflicts.contains(&(c, r));
+
                     let input_square = text_input("", &text_value.to_string())
                         .on_input(move |new_text| {
                             Message::TextChanged(box_id.to_string(), new_text)
@@ -187,6 +432,16 @@ fn create_grid_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Messa
                         .size(25)
                         .width(50)
                         .align_x(alignment::Horizontal::Center)
+                        .style(move |theme: &Theme, status| {
+                            let mut style = text_input::default(theme, status);
+                            if conflict {
+                                let danger: Color = theme.palette().danger;
+                                style.value = danger;
+                                style.border.color = danger;
+                                style.border.width = 2.0;
+                            }
+                            style
+                        })
                         .font(iced::font::Font {
                             // Bold font for the given Sudoku squares
                             weight: if square.given {
@@ -217,7 +472,61 @@ fn create_grid_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Messa
         .style(container::bordered_box)
         .padding(2);
 
-    return bordered_grid;
+    bordered_grid
+}
+
+// Map Ctrl+Z to undo, and Ctrl+Y or Ctrl+Shift+Z to redo (Cmd instead of Ctrl on macOS)
+fn shortcut(event: Event, _status: event::Status, _window: window::Id) -> Option<Message> {
+    let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else {
+        return None;
+    };
+    if !modifiers.command() {
+        return None;
+    }
+    match key.as_ref() {
+        Key::Character("z" | "Z") if modifiers.shift() => Some(Message::Redo),
+        Key::Character("z" | "Z") => Some(Message::Undo),
+        Key::Character("y" | "Y") => Some(Message::Redo),
+        _ => None,
+    }
+}
+
+// Creates checkboxes to turn each of the solver's techniques on or off, in the order they are tried
+fn create_techniques_widget(settings: &SolverSettings) -> Column<'static, Message> {
+    let mut column = Column::new();
+    for techniques in settings.techniques.chunks(6) {
+        let mut techniques_row = Row::new();
+        for technique in techniques {
+            let name: &'static str = technique.name();
+            let label: String = format!("{} ({:.1})", name, technique.difficulty());
+            let technique_checkbox = checkbox(label, settings.is_enabled(name))
+                .on_toggle(move |enabled| Message::TechniqueEnabled(name, enabled))
+                .size(14)
+                .text_size(14);
+            techniques_row = techniques_row.push(Container::new(technique_checkbox).padding(3));
+        }
+        column = column.push(techniques_row);
+    }
+    column
+}
+
+// Creates a scrolling list of the solver's steps so far, numbered in order
+fn create_solve_path_widget(steps: &[SolveStep]) -> Container<'static, Message, Theme> {
+    let mut column = Column::new();
+    for (i, step) in steps.iter().enumerate() {
+        column = column.push(text(format!("{}. [{}] {}", i + 1, step.technique, step.description)).size(14));
+    }
+    Container::new(scrollable(column).height(150)).padding(3)
+}
+
+// Read a whole puzzle pasted into a square, leaving out the square's old value
+fn pasted_puzzle(grid: &[Vec<SudokuSquare>], id: &str, input: &str) -> Option<Sudoku> {
+    let (c, r) = parse_square_id(id)?;
+    let prev_text: String = grid[c][r].value.map(|value| value.to_string()).unwrap_or_default();
+    [Some(input), input.strip_prefix(&prev_text), input.strip_suffix(&prev_text)]
+        .into_iter()
+        .flatten()
+        .find_map(|line| Sudoku::from_line(line).ok())
 }
 
 // Check the Sudoku square text input to display the right character
@@ -225,11 +534,11 @@ fn get_new_square_text(prev: &str, input: &str) -> String {
     // Filter the input to only allow Sudoku digits 1-9
     let filter_text: Vec<char> = input
         .chars()
-        .filter(|c| c.is_digit(10) && *c != '0')
+        .filter(|c| c.is_ascii_digit() && *c != '0')
         .collect();
 
     // Return empty string if no digits found
-    if filter_text.len() == 0 {
+    if filter_text.is_empty() {
         return "".to_string();
     }
 
@@ -244,7 +553,7 @@ fn get_new_square_text(prev: &str, input: &str) -> String {
     }
 
     // Default to returning the last char if the check doesn't hit
-    return filter_text[filter_text.len() - 1].to_string();
+    filter_text[filter_text.len() - 1].to_string()
 }
 
 // Parse the text box Sudoku square ID to get the row and column indices
@@ -252,7 +561,7 @@ fn parse_square_id(id: &str) -> Option<(usize, usize)> {
     let re = Regex::new(r"square-C(\d+)-R(\d+)").unwrap();
     let c: usize;
     let r: usize;
-    if let Some(captures) = re.captures(&id) {
+    if let Some(captures) = re.captures(id) {
         c = captures
             .get(1)
             .unwrap()
@@ -271,16 +580,24 @@ fn parse_square_id(id: &str) -> Option<(usize, usize)> {
             .unwrap()
             .to_digit(10)
             .unwrap() as usize;
-        return Some((c, r));
+        Some((c, r))
     } else {
         println!("Error parsing ID for indices: {}", id);
-        return None;
+        None
     }
 }
 
 
+// Text colors for the color classes of a coloring hint, two for each cluster
+const COLORING_COLORS: [Color; 4] = [
+    Color::from_rgb(0.3, 0.6, 1.0),
+    Color::from_rgb(1.0, 0.6, 0.2),
+    Color::from_rgb(0.4, 0.9, 0.4),
+    Color::from_rgb(1.0, 0.4, 0.8),
+];
+
 // Creates a grid displaying all the options of each square
-fn create_options_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Message, Theme> {
+fn create_options_widget(grid: &[Vec<SudokuSquare>], coloring: Option<&Coloring>) -> Container<'static, Message, Theme> {
     // Create a column widget to hold the rows
     let mut column = Column::new();
 
@@ -312,6 +629,11 @@ fn create_options_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Me
                         None => { vec_to_string(&square.options) },
                     };
 
+                    // Show the color class of the square when the last step was a coloring
+                    let highlight: Option<Color> = coloring
+                        .and_then(|coloring| coloring.color_of(c, r))
+                        .map(|color| COLORING_COLORS[color]);
+
                     let input_square = text_input("", &text_value.to_string())
                         .on_input(move |new_text| {
                             Message::TextChanged(box_id.to_string(), new_text)
@@ -320,6 +642,13 @@ fn create_options_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Me
                         .size(12)
                         .width(55)
                         .align_x(alignment::Horizontal::Center)
+                        .style(move |theme, status| {
+                            let mut style = text_input::default(theme, status);
+                            if let Some(color) = highlight {
+                                style.value = color;
+                            }
+                            style
+                        })
                         .font(iced::font::Font {
                             // Bold font for the given Sudoku squares
                             weight: if square.given {
@@ -350,5 +679,5 @@ fn create_options_widget(grid: &Vec<Vec<SudokuSquare>>) -> Container<'static, Me
         .style(container::bordered_box)
         .padding(2);
 
-    return bordered_grid;
+    bordered_grid
 }
\ No newline at end of file
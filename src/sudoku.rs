@@ -1,81 +1,223 @@
-use std::{default, iter};
+mod als;
+mod chains;
+mod coloring;
+mod contradiction;
+mod file;
+mod fish;
+mod forcing;
+mod generator;
+mod intersections;
+mod rating;
+mod search;
+mod single_digit;
+mod subsets;
+mod technique;
+mod uniqueness;
+mod wings;
 
-#[derive(Default, Debug, Clone)]
+use std::cell::Cell;
+use std::sync::Arc;
+
+pub use coloring::Coloring;
+pub use generator::{random_seed, GeneratorSettings, Symmetry};
+pub use rating::Difficulty;
+pub use technique::{SolveStep, Technique};
+
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct SudokuSquare {
     pub value: Option<u32>,
     pub options: Vec<u32>,
     pub given: bool,
 }
 
+// Options controlling which techniques the solver may use
+#[derive(Debug, Clone)]
+pub struct SolverSettings {
+    // Use uniqueness techniques even when the grid is not known to have a single solution
+    pub assume_unique: bool,
+    // The techniques to try, in order, each time the solver looks for the next step
+    pub techniques: Vec<Arc<dyn Technique>>,
+    // Names of the techniques which are skipped
+    pub disabled: Vec<&'static str>,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        SolverSettings {
+            assume_unique: false,
+            techniques: technique::default_techniques(),
+            disabled: vec![],
+        }
+    }
+}
+
+impl SolverSettings {
+    // Turn off every technique besides the naked and hidden singles, for beginner hints, or turn them all back on
+    pub fn set_singles_only(&mut self, singles_only: bool) {
+        self.disabled.clear();
+        if singles_only {
+            self.disabled = self
+                .techniques
+                .iter()
+                .map(|technique| technique.name())
+                .filter(|name| !technique::SINGLES.contains(name))
+                .collect();
+        }
+    }
+
+    // Check whether the solver may use the technique with this name
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.contains(&name)
+    }
+
+    // Turn the technique with this name on or off
+    pub fn set_enabled(&mut self, name: &'static str, enabled: bool) {
+        self.disabled.retain(|&disabled| disabled != name);
+        if !enabled {
+            self.disabled.push(name);
+        }
+    }
+
+    // Check whether every technique besides the singles is turned off
+    pub fn is_singles_only(&self) -> bool {
+        self.techniques
+            .iter()
+            .all(|technique| technique::SINGLES.contains(&technique.name()) || !self.is_enabled(technique.name()))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Sudoku {
     pub grid: Vec<Vec<SudokuSquare>>,
     pub solved: bool,
     pub status: String,
+    // Whether the square options are up to date with the grid, so eliminations can build on them
+    pub options_ready: bool,
+    // The coloring used by the last solver step, if it was one, to show in the options grid
+    pub coloring: Option<Coloring>,
+    // Whether the grid has exactly one solution, once it has been checked
+    pub unique: Cell<Option<bool>>,
+    pub settings: SolverSettings,
 }
 
 impl Default for Sudoku {
     fn default() -> Self {
+        Sudoku::new(empty_board())
+    }
+}
+
+impl Sudoku {
+    // Create a Sudoku with the grid and default settings
+    pub fn new(grid: Vec<Vec<SudokuSquare>>) -> Self {
         Sudoku {
-            grid: hard_board_1(),
-            // grid: vec![vec![SudokuSquare::default(); 9]; 9],
+            grid,
             solved: false,
             status: String::default(),
+            options_ready: false,
+            coloring: None,
+            unique: Cell::new(None),
+            settings: SolverSettings::default(),
         }
     }
-}
 
-impl Sudoku {
     // Solve the puzzle! Public function called on solve button
-    pub fn solve(&mut self, next: bool) {
+    // Returns the steps taken, in order, as the solution path
+    pub fn solve(&mut self, next: bool) -> Vec<SolveStep> {
         // Solve the Sudoku puzzle
-        
-        let mut iteration_change: bool = true;
-
-        'iteration_loop: while iteration_change {
-            iteration_change = false;
-
-            // Loop Solve 1: Update square if row, column, or box has only one option
-            for r in 0..9 {
-                for c in 0..9 {
-                    if self.grid[r][c].value.is_none() {
-                        self.update_options(r, c);
-                        if self.grid[r][c].options.len() == 1 {
-                            self.set_square(r, c, self.grid[r][c].options[0], false);
-                            println!("One option: {} at R={}, C={}", self.grid[r][c].value.unwrap(), r + 1, c + 1);
-                            iteration_change = true;
-                            if next { return; }
-                            else { continue 'iteration_loop; }
-                        }
-                    }
-                }
-            }
+        let mut steps: Vec<SolveStep> = vec![];
 
-            // Loop Solve 2: Update square if only square in row, column, or box with option
-            // This is in a separate loop to ensure that all the options are updated before checking
-            for r in 0..9 {
-                for c in 0..9 {
-                    if self.grid[r][c].value.is_none() {
-                        iteration_change = self.update_only_options(r, c);
-                        if iteration_change { 
-                            if next { return; }
-                            else { continue 'iteration_loop; }
-                        } 
-                    }
-                }
+        // Options are only listed from scratch once, after which the techniques below narrow them down
+        if !self.options_ready {
+            self.update_all_options();
+        }
+        // A broken grid can't be solved, so say what is wrong rather than searching for steps
+        if self.report_contradiction() {
+            return steps;
+        }
+
+        // Take one step at a time from the first technique which makes progress, starting again
+        // from the top of the list after every step
+        while let Some(step) = self.next_step() {
+            self.apply_step(&step);
+            steps.push(step);
+            if self.report_contradiction() || next {
+                return steps;
             }
         }
+        // Only the coloring from the step just taken is shown
+        self.coloring = None;
 
         if next {
-            println!("Solve Next unable to find next value");
+            self.status = "Solve Next unable to find next value".to_string();
         } 
         else {
             self.check_solved();
             if !self.solved {
-                self.status = "Unable to solve this Sudoku grid".to_string();
-                println!("{}", self.status);
+                // Fall back to a depth-first search once the logical techniques stall
+                let empty: Vec<(usize, usize)> = (0..81)
+                    .map(|i| (i / 9, i % 9))
+                    .filter(|&(r, c)| self.grid[r][c].value.is_none())
+                    .collect();
+                match self.solve_backtrack() {
+                    Some(guesses) => {
+                        // The search doesn't explain itself, so each value it found is its own step
+                        for (r, c) in empty {
+                            if let Some(value) = self.grid[r][c].value {
+                                let description: String = format!("Backtracking: {} at {}", value, square_name(r, c));
+                                steps.push(SolveStep { technique: "Backtracking", ..SolveStep::place(r, c, value, description) });
+                            }
+                        }
+                        self.check_solved();
+                        if self.solved {
+                            self.status = format!("Sudoku puzzle solved with {} guesses", guesses);
+                        }
+                    }
+                    None => {
+                        self.status = "Unable to solve this Sudoku grid".to_string();
+                    }
+                }
             }
         }
+        steps
+    }
+
+    // Find the next step using the enabled techniques in order, without changing the grid
+    pub fn next_step(&self) -> Option<SolveStep> {
+        self.settings
+            .techniques
+            .iter()
+            .filter(|technique| self.settings.is_enabled(technique.name()))
+            .find_map(|technique| technique::find_step_with(technique.as_ref(), self))
+    }
+
+    // Apply a step to the grid, showing it as the status
+    pub fn apply_step(&mut self, step: &SolveStep) {
+        for &(r, c, value) in &step.eliminations {
+            self.remove_option(r, c, value);
+        }
+        if let Some((r, c, value)) = step.placement {
+            self.set_square(r, c, value, false);
+        }
+        self.coloring = step.coloring.clone();
+        self.status = step.description.clone();
+    }
+
+    // Forget what was worked out from the grid after it is edited by hand
+    pub fn grid_edited(&mut self) {
+        self.options_ready = false;
+        self.unique.set(None);
+    }
+
+    // List the possible options for every empty square from scratch
+    pub fn update_all_options(&mut self) {
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_none() {
+                    self.update_options(r, c);
+                }
+            }
+        }
+        self.options_ready = true;
     }
 
     pub fn update_options(&mut self, r: usize, c: usize) {
@@ -143,11 +285,11 @@ impl Sudoku {
         options
     }
 
-    // Determine if the square is the only one in its row with a certain option
-    fn row_only_option(&self, r: usize, c: usize) -> Option<u32> {
+    // Determine if the square is the only one in its column with a certain option
+    fn col_only_option(&self, r: usize, c: usize) -> Option<u32> {
         let square_options: &Vec<u32> = &self.grid[r][c].options;
         'option_loop: for option in square_options {
-            // Check every other square in the row to determine if the option
+            // Check every other square in the column to determine if the option
             // is only applicable in this square
             for i in 0..9usize {
                 // Do not check the square in question or filled squares
@@ -161,17 +303,16 @@ impl Sudoku {
                 }
             }
             // If you get here, no other square has this option. Return it.
-            println!("Only row option: {} at R={}, C={}", *option, r + 1, c + 1);
             return Some(*option);
         }
         None
     }
 
-    // Determine if the square is the only one in its column with a certain option
-    fn col_only_option(&self, r: usize, c: usize) -> Option<u32> {
+    // Determine if the square is the only one in its row with a certain option
+    fn row_only_option(&self, r: usize, c: usize) -> Option<u32> {
         let square_options: &Vec<u32> = &self.grid[r][c].options;
         'option_loop: for option in square_options {
-            // Check every other square in the column to determine if the option
+            // Check every other square in the row to determine if the option
             // is only applicable in this square
             for j in 0..9usize {
                 // Do not check the square in question or filled squares
@@ -185,7 +326,6 @@ impl Sudoku {
                 }
             }
             // If you get here, no other square has this option. Return it.
-            println!("Only col option: {} at R={}, C={}", *option, r + 1, c + 1);
             return Some(*option);
         }
         None
@@ -212,39 +352,97 @@ impl Sudoku {
                 }
             }
             // If you get here, no other square has this option. Return it.
-            println!("Only box option: {} at R={}, C={}", *option, r + 1, c + 1);
             return Some(*option);
         }
         None
     }
 
-    // Check the row, column, and box to determine if the square has an only option, and set value if found
-    fn update_only_options(&mut self, r: usize, c: usize) -> bool {
-        // Row only option check
-        match self.row_only_option(r, c) {
-            None => { },
-            Some(i) => {
-                self.set_square(r, c, i, false);
-                return true;
+    // Naked single: an empty square with only one option left must hold it
+    fn find_naked_single(&self) -> Option<SolveStep> {
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_none() && self.grid[r][c].options.len() == 1 {
+                    let value: u32 = self.grid[r][c].options[0];
+                    return Some(SolveStep::place(r, c, value, format!("Naked Single: {} at {}", value, square_name(r, c))));
+                }
             }
         }
-        // Column only option check
-        match self.col_only_option(r, c) {
-            None => { },
-            Some(i) => {
-                self.set_square(r, c, i, false);
-                return true;
+        None
+    }
+
+    // Hidden single: a square which is the only one in its row, column, or box with an option must hold it
+    fn find_hidden_single(&self) -> Option<SolveStep> {
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_some() {
+                    continue;
+                }
+                // A hidden single in a box is easier to spot than one in a row or column
+                let only_options = [
+                    ("box", self.box_only_option(r, c), 1.2),
+                    ("column", self.col_only_option(r, c), 1.5),
+                    ("row", self.row_only_option(r, c), 1.5),
+                ];
+                for (unit, only_option, difficulty) in only_options {
+                    if let Some(value) = only_option {
+                        let description: String = format!("Hidden Single: {} at {}, the only place in its {}", value, square_name(r, c), unit);
+                        return Some(SolveStep::place(r, c, value, description).rated(difficulty));
+                    }
+                }
             }
         }
-        // Box only option check
-        match self.box_only_option(r, c) {
-            None => { },
-            Some(i) => {
-                self.set_square(r, c, i, false);
-                return true;
+        None
+    }
+
+    // Remove an option from a square, returning whether it was there to remove
+    pub fn remove_option(&mut self, r: usize, c: usize, option: u32) -> bool {
+        let options: &mut Vec<u32> = &mut self.grid[r][c].options;
+        let len: usize = options.len();
+        options.retain(|&x| x != option);
+        options.len() != len
+    }
+
+    // Remove a newly placed value from the options of every square in its row, column, and box
+    pub fn remove_peer_options(&mut self, r: usize, c: usize, value: u32) {
+        let b: usize = (r / 3) * 3 + c / 3;
+        for unit in [r, 9 + c, 18 + b] {
+            for (i, j) in unit_squares(unit) {
+                self.remove_option(i, j, value);
             }
         }
-        return false;
+    }
+
+    // List the empty squares out of the given ones which have the value as an option
+    fn option_squares(&self, squares: &[(usize, usize)], value: u32) -> Vec<(usize, usize)> {
+        squares
+            .iter()
+            .filter(|&&(r, c)| self.grid[r][c].value.is_none() && self.grid[r][c].options.contains(&value))
+            .copied()
+            .collect()
+    }
+
+    // Find the two squares of a strong link: a unit where the value is an option in exactly two squares
+    fn strong_link(&self, unit: usize, value: u32) -> Option<[(usize, usize); 2]> {
+        match self.option_squares(&unit_squares(unit), value)[..] {
+            [a, b] => Some([a, b]),
+            _ => None,
+        }
+    }
+
+    // List the empty squares which have the value as an option and see every one of the given squares
+    fn squares_seeing_all(&self, squares: &[(usize, usize)], value: u32) -> Vec<(usize, usize)> {
+        let mut seeing: Vec<(usize, usize)> = vec![];
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_none()
+                    && self.grid[r][c].options.contains(&value)
+                    && squares.iter().all(|&square| sees(square, (r, c)))
+                {
+                    seeing.push((r, c));
+                }
+            }
+        }
+        seeing
     }
 
     // Check the Sudoku puzzle to see if it is successfully solved
@@ -272,9 +470,12 @@ impl Sudoku {
             self.status = "Sudoku puzzle solved!".to_string();
         }
         else {
-            self.status = "Sudoku puzzle is incorrect".to_string();
+            // Point out a repeated value if there is one, since that is the mistake to fix
+            self.status = match self.find_duplicate() {
+                Some(duplicate) => format!("Sudoku puzzle is incorrect: {}", duplicate.description()),
+                None => "Sudoku puzzle is incorrect".to_string(),
+            };
         }
-        println!("{}", self.status);
     }
 
     fn is_row_solved(&self, r: usize) -> bool {
@@ -288,7 +489,7 @@ impl Sudoku {
             }
             return false;
         }
-        return true;
+        true
     }
 
     fn is_col_solved(&self, c: usize) -> bool {
@@ -302,7 +503,7 @@ impl Sudoku {
             }
             return false;
         }
-        return true;
+        true
     }
 
     fn is_box_solved(&self, b: usize) -> bool {
@@ -321,12 +522,66 @@ impl Sudoku {
             }
             return false;
         }
-        return true;
+        true
+    }
+}
+
+// List the (row, column) squares in one of the 27 units: rows 0-8, columns 9-17, and 3x3 boxes 18-26
+fn unit_squares(unit: usize) -> Vec<(usize, usize)> {
+    (0..9usize)
+        .map(|i| match unit {
+            0..=8 => (unit, i),
+            9..=17 => (i, unit - 9),
+            _ => ((unit - 18) / 3 * 3 + i / 3, (unit - 18) % 3 * 3 + i % 3),
+        })
+        .collect()
+}
+
+// Determine if two different squares share a row, column, or 3x3 box
+fn sees(a: (usize, usize), b: (usize, usize)) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || (a.0 / 3 == b.0 / 3 && a.1 / 3 == b.1 / 3))
+}
+
+// Name a unit for display, e.g. "row 4"
+fn unit_name(unit: usize) -> String {
+    match unit {
+        0..=8 => format!("row {}", unit + 1),
+        9..=17 => format!("column {}", unit - 8),
+        _ => format!("box {}", unit - 17),
     }
 }
 
+// Name a square for display, e.g. "R4C7"
+fn square_name(r: usize, c: usize) -> String {
+    format!("R{}C{}", r + 1, c + 1)
+}
+
+// Helper function to print out a list of squares, e.g. "R1C2, R1C5"
+fn squares_to_string(squares: &[(usize, usize)]) -> String {
+    squares
+        .iter()
+        .map(|&(r, c)| square_name(r, c))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// List every way to choose k items from the slice, keeping their order
+fn combinations<T: Copy>(items: &[T], k: usize) -> Vec<Vec<T>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let mut combos: Vec<Vec<T>> = vec![];
+    for i in 0..items.len() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, items[i]);
+            combos.push(rest);
+        }
+    }
+    combos
+}
+
 // Helper function to print out a Vec<u32> as a string
-pub fn vec_to_string(vec: &Vec<u32>) -> String {
+pub fn vec_to_string(vec: &[u32]) -> String {
     let mut str: String = "".to_string();
     for num in vec {
         str += &num.to_string();
@@ -339,7 +594,7 @@ pub fn vec_to_string(vec: &Vec<u32>) -> String {
 
 // Shorthand for creating a new SudokuSquare struct
 fn sqr(value: u32) -> SudokuSquare {
-    if value >= 1 && value <= 9 {
+    if (1..=9).contains(&value) {
         SudokuSquare { value: Some(value), options: Vec::default(), given: true }
     } else {
         SudokuSquare { value: None, options: Vec::default(), given: false }
@@ -347,6 +602,7 @@ fn sqr(value: u32) -> SudokuSquare {
 }
 
 
+#[cfg(test)]
 pub fn med_board_1() -> Vec<Vec<SudokuSquare>> {
     vec![
         vec![ sqr(0), sqr(1), sqr(0), /**/ sqr(0), sqr(0), sqr(8), /**/ sqr(0), sqr(9), sqr(0) ],
@@ -363,22 +619,6 @@ pub fn med_board_1() -> Vec<Vec<SudokuSquare>> {
     ]
 }
 
-pub fn hard_board_1() -> Vec<Vec<SudokuSquare>> {
-    vec![
-        vec![ sqr(4), sqr(3), sqr(0), /**/ sqr(0), sqr(0), sqr(0), /**/ sqr(0), sqr(8), sqr(7) ],
-        vec![ sqr(0), sqr(0), sqr(2), /**/ sqr(0), sqr(1), sqr(0), /**/ sqr(0), sqr(5), sqr(0) ],
-        vec![ sqr(0), sqr(0), sqr(0), /**/ sqr(0), sqr(0), sqr(3), /**/ sqr(1), sqr(4), sqr(0) ],
-        /**************************************************************************************/
-        vec![ sqr(2), sqr(0), sqr(9), /**/ sqr(0), sqr(0), sqr(0), /**/ sqr(4), sqr(0), sqr(0) ],
-        vec![ sqr(0), sqr(0), sqr(4), /**/ sqr(1), sqr(0), sqr(9), /**/ sqr(8), sqr(0), sqr(0) ],
-        vec![ sqr(0), sqr(0), sqr(3), /**/ sqr(0), sqr(0), sqr(0), /**/ sqr(7), sqr(0), sqr(5) ],
-        /**************************************************************************************/
-        vec![ sqr(0), sqr(9), sqr(8), /**/ sqr(4), sqr(0), sqr(0), /**/ sqr(0), sqr(0), sqr(0) ],
-        vec![ sqr(0), sqr(2), sqr(0), /**/ sqr(0), sqr(5), sqr(0), /**/ sqr(9), sqr(0), sqr(0) ],
-        vec![ sqr(3), sqr(4), sqr(0), /**/ sqr(0), sqr(0), sqr(0), /**/ sqr(0), sqr(2), sqr(8) ],
-    ]
-}
-
 pub fn empty_board() -> Vec<Vec<SudokuSquare>> {
     vec![
         vec![ sqr(0), sqr(0), sqr(0), /**/ sqr(0), sqr(0), sqr(0), /**/ sqr(0), sqr(0), sqr(0) ],
@@ -0,0 +1,361 @@
+use super::{sees, square_name, squares_to_string, unit_squares, vec_to_string, SolveStep, Sudoku};
+
+// An almost locked set: N empty squares within one unit with N + 1 options between them
+#[derive(Debug, Clone)]
+pub struct Als {
+    pub squares: Vec<(usize, usize)>,
+    pub values: Vec<u32>,
+    // Bitmask of the squares (bit r * 9 + c), and the same for only the squares with each value
+    square_mask: u128,
+    value_masks: [u128; 10],
+}
+
+impl Als {
+    // Describe the set for display, e.g. "R1C1, R1C2 {127}"
+    fn description(&self) -> String {
+        format!("{} {{{}}}", squares_to_string(&self.squares), vec_to_string(&self.values))
+    }
+}
+
+impl Sudoku {
+    // Find the first ALS-XZ, ALS-XY-Wing, or Death Blossom which removes at least one option
+    pub fn find_als(&self) -> Option<SolveStep> {
+        let sets: Vec<Als> = self.almost_locked_sets();
+        let peers: Vec<u128> = peer_masks();
+        self.find_als_xz(&sets, &peers)
+            .or_else(|| self.find_als_xy_wing(&sets, &peers))
+            .or_else(|| self.find_death_blossom(&sets, &peers))
+    }
+
+    // List every almost locked set in the rows, columns, and boxes, each set only once
+    pub fn almost_locked_sets(&self) -> Vec<Als> {
+        let mut sets: Vec<Als> = vec![];
+        for unit in 0..27 {
+            let empty: Vec<(usize, usize)> = unit_squares(unit)
+                .into_iter()
+                .filter(|&(r, c)| self.grid[r][c].value.is_none())
+                .collect();
+
+            // Check every subset of the unit's empty squares
+            for subset in 1..(1u32 << empty.len()) {
+                let squares: Vec<(usize, usize)> =
+                    (0..empty.len()).filter(|i| subset & (1 << i) != 0).map(|i| empty[i]).collect();
+                let values: Vec<u32> = (1..=9u32)
+                    .filter(|value| squares.iter().any(|&(r, c)| self.grid[r][c].options.contains(value)))
+                    .collect();
+                if values.len() != squares.len() + 1 {
+                    continue;
+                }
+
+                let square_mask: u128 = squares.iter().fold(0, |mask, &(r, c)| mask | square_bit(r, c));
+                if sets.iter().any(|set| set.square_mask == square_mask) {
+                    continue;
+                }
+                let mut value_masks: [u128; 10] = [0; 10];
+                for &(r, c) in &squares {
+                    for &option in &self.grid[r][c].options {
+                        value_masks[option as usize] |= square_bit(r, c);
+                    }
+                }
+                sets.push(Als { squares, values, square_mask, value_masks });
+            }
+        }
+        sets
+    }
+
+    // ALS-XZ: two sets share a restricted common value X, where every X in one sees every X in the
+    // other, so at most one of them holds X and the other is locked. Any other shared value Z must
+    // then be in one of the sets, and is removed from squares seeing every Z in both
+    fn find_als_xz(&self, sets: &[Als], peers: &[u128]) -> Option<SolveStep> {
+        for (i, a) in sets.iter().enumerate() {
+            for b in &sets[i + 1..] {
+                let links: Vec<u32> = restricted_commons(a, b, peers);
+                if links.is_empty() {
+                    continue;
+                }
+                for &z in a.values.iter().filter(|&&z| b.values.contains(&z) && !links.contains(&z)) {
+                    let eliminations = self.als_eliminations(&[a, b], z, peers);
+                    if !eliminations.is_empty() {
+                        return Some(als_step("ALS-XZ", &[a, b], None, &[links[0]], z, &eliminations));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // ALS-XY-Wing: a pivot set C shares restricted common value X with set A and Y with set B.
+    // C holds at least one of X and Y, which locks A or B, so a value Z shared by A and B must be
+    // in one of them, and is removed from squares seeing every Z in both
+    fn find_als_xy_wing(&self, sets: &[Als], peers: &[u128]) -> Option<SolveStep> {
+        for c in sets {
+            let wings: Vec<(&Als, Vec<u32>)> = sets
+                .iter()
+                .map(|set| (set, restricted_commons(set, c, peers)))
+                .filter(|(_, links)| !links.is_empty())
+                .collect();
+
+            for (i, (a, a_links)) in wings.iter().enumerate() {
+                for (b, b_links) in &wings[i + 1..] {
+                    if a.square_mask & b.square_mask != 0 {
+                        continue;
+                    }
+                    for &x in a_links {
+                        for &y in b_links.iter().filter(|&&y| y != x) {
+                            for &z in a.values.iter().filter(|&&z| b.values.contains(&z) && z != x && z != y) {
+                                let eliminations = self.als_eliminations(&[a, b], z, peers);
+                                if !eliminations.is_empty() {
+                                    return Some(als_step("ALS-XY-Wing", &[a, b, c], None, &[x, y], z, &eliminations));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Death Blossom: a stem square where each of its options sees every copy of that value in
+    // its own petal set. Whichever value the stem takes, that petal is locked, so a value Z in
+    // every petal (but not the stem) is removed from squares seeing every Z in all the petals
+    fn find_death_blossom(&self, sets: &[Als], peers: &[u128]) -> Option<SolveStep> {
+        for r in 0..9 {
+            for c in 0..9 {
+                let options: &Vec<u32> = &self.grid[r][c].options;
+                if self.grid[r][c].value.is_some() || options.len() < 2 || options.len() > 3 {
+                    continue;
+                }
+
+                // The possible petals for each stem option
+                let stem_bit: u128 = square_bit(r, c);
+                let petals: Vec<Vec<&Als>> = options
+                    .iter()
+                    .map(|&option| {
+                        let mask: u128 = peers[r * 9 + c];
+                        sets.iter()
+                            .filter(|set| {
+                                let value_mask = set.value_masks[option as usize];
+                                set.square_mask & stem_bit == 0 && value_mask != 0 && value_mask & !mask == 0
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                let mut chosen: Vec<&Als> = vec![];
+                if let Some(step) = self.choose_petals(&petals, &mut chosen, (r, c), peers) {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+
+    // Pick one petal for each stem option in turn, checking the eliminations once all are chosen
+    fn choose_petals<'a>(
+        &self,
+        petals: &[Vec<&'a Als>],
+        chosen: &mut Vec<&'a Als>,
+        stem: (usize, usize),
+        peers: &[u128],
+    ) -> Option<SolveStep> {
+        let stem_options: &Vec<u32> = &self.grid[stem.0][stem.1].options;
+        let common: Vec<u32> = (1..=9u32)
+            .filter(|z| !stem_options.contains(z) && chosen.iter().all(|set| set.values.contains(z)))
+            .collect();
+        if common.is_empty() {
+            return None;
+        }
+
+        if chosen.len() == petals.len() {
+            for &z in &common {
+                let eliminations: Vec<(usize, usize)> = self
+                    .als_eliminations(chosen, z, peers)
+                    .into_iter()
+                    .filter(|&square| square != stem)
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(als_step("Death Blossom", chosen, Some(stem), stem_options, z, &eliminations));
+                }
+            }
+            return None;
+        }
+
+        for &petal in &petals[chosen.len()] {
+            if chosen.iter().any(|set| set.square_mask & petal.square_mask != 0) {
+                continue;
+            }
+            chosen.push(petal);
+            let step = self.choose_petals(petals, chosen, stem, peers);
+            chosen.pop();
+            if step.is_some() {
+                return step;
+            }
+        }
+        None
+    }
+
+    // List the empty squares outside the sets which have the value and see every copy of it in the sets
+    fn als_eliminations(&self, sets: &[&Als], value: u32, peers: &[u128]) -> Vec<(usize, usize)> {
+        let mut seeing: u128 = !0;
+        for set in sets {
+            let mut mask: u128 = set.value_masks[value as usize];
+            while mask != 0 {
+                let i = mask.trailing_zeros() as usize;
+                seeing &= peers[i];
+                mask &= mask - 1;
+            }
+        }
+
+        let mut eliminations: Vec<(usize, usize)> = vec![];
+        for r in 0..9 {
+            for c in 0..9 {
+                if seeing & square_bit(r, c) != 0
+                    && self.grid[r][c].value.is_none()
+                    && self.grid[r][c].options.contains(&value)
+                    && sets.iter().all(|set| set.square_mask & square_bit(r, c) == 0)
+                {
+                    eliminations.push((r, c));
+                }
+            }
+        }
+        eliminations
+    }
+}
+
+// List the restricted common values of two separate sets: values in both where every square
+// with the value in one set sees every square with it in the other
+fn restricted_commons(a: &Als, b: &Als, peers: &[u128]) -> Vec<u32> {
+    if a.square_mask & b.square_mask != 0 {
+        return vec![];
+    }
+    a.values
+        .iter()
+        .filter(|&&value| {
+            let b_mask: u128 = b.value_masks[value as usize];
+            if b_mask == 0 {
+                return false;
+            }
+            let mut a_mask: u128 = a.value_masks[value as usize];
+            while a_mask != 0 {
+                let i = a_mask.trailing_zeros() as usize;
+                if peers[i] & b_mask != b_mask {
+                    return false;
+                }
+                a_mask &= a_mask - 1;
+            }
+            true
+        })
+        .copied()
+        .collect()
+}
+
+// The bit for a square in a square bitmask
+fn square_bit(r: usize, c: usize) -> u128 {
+    1 << (r * 9 + c)
+}
+
+// For each square, the bitmask of the squares it sees
+fn peer_masks() -> Vec<u128> {
+    (0..81usize)
+        .map(|i| {
+            (0..81usize)
+                .filter(|&j| sees((i / 9, i % 9), (j / 9, j % 9)))
+                .fold(0, |mask, j| mask | square_bit(j / 9, j % 9))
+        })
+        .collect()
+}
+
+// Build the step for a deduction, e.g. "ALS-XZ: R1C1, R1C2 {127} and R3C5 {27} linked by 7, removed 2 from R2C3".
+// The links are the restricted common values between the sets, or the stem's options for a Death Blossom
+fn als_step(
+    name: &str,
+    sets: &[&Als],
+    stem: Option<(usize, usize)>,
+    links: &[u32],
+    value: u32,
+    eliminations: &[(usize, usize)],
+) -> SolveStep {
+    let set_names: Vec<String> = sets.iter().map(|set| set.description()).collect();
+    let stem_name: String = match stem {
+        Some((r, c)) => format!("stem {} with petals ", square_name(r, c)),
+        None => String::default(),
+    };
+    let description: String = format!(
+        "{}: {}{} linked by {}, removed {} from {}",
+        name,
+        stem_name,
+        set_names.join(" and "),
+        vec_to_string(links),
+        value,
+        squares_to_string(eliminations)
+    );
+
+    let mut squares: Vec<(usize, usize)> = stem.into_iter().collect();
+    for set in sets {
+        squares.extend(set.squares.iter());
+    }
+    let difficulty: f32 = match name {
+        "ALS-XZ" => 7.0,
+        "ALS-XY-Wing" => 7.2,
+        _ => 7.5,
+    };
+    SolveStep::remove(value, eliminations, description).involving(&squares).rated(difficulty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_an_als_xz() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_als(), "ALS-XZ", None, &[(5, 0, 1)]);
+    }
+
+    #[test]
+    fn finds_an_als_xy_wing() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3   | 6  4  7
+             8    1   6 | 4   9  7   | 3  2  5
+             4    7   3 | 5   6  2   | 1  9  8
+             3    4   7 | 8   2  16  | 9  5  16
+             169  8   2 | 69  4  5   | 7  3  16
+             169  69  5 | 3   7  169 | 4  8  2
+             7    3   4 | 2   1  8   | 5  6  9
+             69   5   8 | 7   3  69  | 2  1  4
+             2    69  1 | 69  5  4   | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_als(), "ALS-XY-Wing", None, &[(5, 0, 6)]);
+    }
+
+    #[test]
+    fn finds_a_death_blossom() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "28   6    78  | 2578   1235   9   | 4     1235   1237
+             5    148  3   | 278    24     17  | 167   126    9
+             29   19   479 | 257    12345  6   | 1357  1235   8
+             369  479  459 | 25679  125    137 | 1378  1368   1367
+             368  78   58  | 56     15     4   | 2     9      1367
+             369  2    1   | 679    8      37  | 367   4      5
+             1    5    68  | 3      7      2   | 9     68     4
+             4    89   289 | 1      6      58  | 358   7      23
+             7    3    268 | 4      9      58  | 1568  12568  126",
+        )
+        .unwrap();
+        assert_step(sudoku.find_als(), "Death Blossom", None, &[(0, 4, 2)]);
+    }
+}
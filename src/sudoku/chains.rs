@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+
+use super::{sees, square_name, unit_squares, SolveStep, Sudoku};
+
+// A chain's candidates and the options it removes, as (row, column, value)
+type Chain = (Vec<(usize, usize, u32)>, Vec<(usize, usize, u32)>);
+
+// The kinds of links a chain search may use
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChainKind {
+    // One value only, linked through units
+    X,
+    // Bivalue squares only, linked to each other through shared values
+    XY,
+    // Any mix of the two
+    Aic,
+}
+
+impl Sudoku {
+    // Find the shortest X-Chain which removes at least one option, then XY-Chain, then general AIC
+    pub fn find_chain(&self) -> Option<SolveStep> {
+        [(ChainKind::X, "X-Chain"), (ChainKind::XY, "XY-Chain"), (ChainKind::Aic, "AIC")]
+            .into_iter()
+            .find_map(|(kind, name)| {
+                self.find_chain_of_kind(kind).map(|(nodes, eliminations)| chain_step(name, &nodes, eliminations))
+            })
+    }
+
+    // Search out from every candidate along alternating strong and weak links, keeping the
+    // shortest chain which removes an option. The chain is its candidates in order as (row, column,
+    // value), with links alternating strong and weak and starting and ending with a strong link,
+    // so at least one of the two ends holds its value
+    fn find_chain_of_kind(&self, kind: ChainKind) -> Option<Chain> {
+        let (strong, weak) = self.chain_links(kind);
+        let mut best: Option<Chain> = None;
+
+        for start in 0..729usize {
+            if strong[start].is_empty() {
+                continue;
+            }
+
+            // Each state is a candidate and whether it was reached by a strong link (0) or a weak
+            // link (1), which decides the kind of link to follow next
+            let mut previous: Vec<Option<usize>> = vec![None; 729 * 2];
+            let mut visited: Vec<bool> = vec![false; 729 * 2];
+            let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+            visited[start * 2 + 1] = true;
+            queue.push_back((start * 2 + 1, 0));
+
+            while let Some((state, length)) = queue.pop_front() {
+                // A longer chain than the best found so far is not worth following
+                if best.as_ref().is_some_and(|(nodes, _)| length + 2 >= nodes.len()) {
+                    break;
+                }
+
+                let (node, reached_weak) = (state / 2, state % 2 == 1);
+                let links: &Vec<usize> = if reached_weak { &strong[node] } else { &weak[node] };
+                for &next in links {
+                    let next_state = next * 2 + if reached_weak { 0 } else { 1 };
+                    if visited[next_state] {
+                        continue;
+                    }
+                    visited[next_state] = true;
+                    previous[next_state] = Some(state);
+                    queue.push_back((next_state, length + 1));
+
+                    // A chain needs at least three links and must end on a strong link
+                    if !reached_weak || length + 1 < 3 || next == start {
+                        continue;
+                    }
+                    let eliminations = self.chain_eliminations(start, next);
+                    if !eliminations.is_empty() {
+                        let mut nodes: Vec<(usize, usize, u32)> = vec![];
+                        let mut current: Option<usize> = Some(next_state);
+                        while let Some(state) = current {
+                            nodes.insert(0, candidate_of(state / 2));
+                            current = previous[state];
+                        }
+                        best = Some((nodes, eliminations));
+                        break;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    // Work out what can be removed knowing that at least one of the two end candidates is true
+    fn chain_eliminations(&self, start: usize, end: usize) -> Vec<(usize, usize, u32)> {
+        let (r1, c1, v1) = candidate_of(start);
+        let (r2, c2, v2) = candidate_of(end);
+        let mut eliminations: Vec<(usize, usize, u32)> = vec![];
+
+        if v1 == v2 {
+            // The value is removed from every square seeing both ends
+            for (r, c) in self.squares_seeing_all(&[(r1, c1), (r2, c2)], v1) {
+                eliminations.push((r, c, v1));
+            }
+        } else if (r1, c1) == (r2, c2) {
+            // Both ends are in the same square, so it can only hold one of the two values
+            for &option in &self.grid[r1][c1].options {
+                if option != v1 && option != v2 {
+                    eliminations.push((r1, c1, option));
+                }
+            }
+        } else if sees((r1, c1), (r2, c2)) {
+            // The ends see each other, so each end's value is removed from the other end's square
+            if self.grid[r2][c2].options.contains(&v1) {
+                eliminations.push((r2, c2, v1));
+            }
+            if self.grid[r1][c1].options.contains(&v2) {
+                eliminations.push((r1, c1, v2));
+            }
+        }
+        eliminations
+    }
+
+    // List the strong and weak links of every candidate which the kind of chain may use
+    fn chain_links(&self, kind: ChainKind) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        let mut strong: Vec<Vec<usize>> = vec![vec![]; 729];
+        let mut weak: Vec<Vec<usize>> = vec![vec![]; 729];
+
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_some() {
+                    continue;
+                }
+                let options: &Vec<u32> = &self.grid[r][c].options;
+                let b: usize = (r / 3) * 3 + c / 3;
+                for &value in options {
+                    let node = candidate_index(r, c, value);
+
+                    // Strong link to the other square of a unit where the value has only two squares
+                    if kind != ChainKind::XY {
+                        for unit in [r, 9 + c, 18 + b] {
+                            if let Some([a, other]) = self.strong_link(unit, value) {
+                                let other = if a == (r, c) { other } else { a };
+                                let other_node = candidate_index(other.0, other.1, value);
+                                if !strong[node].contains(&other_node) {
+                                    strong[node].push(other_node);
+                                }
+                            }
+                        }
+                    }
+                    // Strong link to the other value of a square with only two options
+                    if kind != ChainKind::X && options.len() == 2 {
+                        let other: u32 = if options[0] == value { options[1] } else { options[0] };
+                        strong[node].push(candidate_index(r, c, other));
+                    }
+
+                    // Weak links to the same value in every square this one sees
+                    for unit in [r, 9 + c, 18 + b] {
+                        for (i, j) in self.option_squares(&unit_squares(unit), value) {
+                            let other_node = candidate_index(i, j, value);
+                            if (i, j) != (r, c) && !weak[node].contains(&other_node) {
+                                if kind == ChainKind::XY && self.grid[i][j].options.len() != 2 {
+                                    continue;
+                                }
+                                weak[node].push(other_node);
+                            }
+                        }
+                    }
+                    // Weak links to the other values of the same square
+                    if kind == ChainKind::Aic {
+                        for &other in options.iter().filter(|&&other| other != value) {
+                            weak[node].push(candidate_index(r, c, other));
+                        }
+                    }
+                }
+            }
+        }
+        (strong, weak)
+    }
+}
+
+// Number each of the 729 possible candidates
+fn candidate_index(r: usize, c: usize, value: u32) -> usize {
+    (r * 9 + c) * 9 + value as usize - 1
+}
+
+// Turn a candidate number back into (row, column, value)
+fn candidate_of(index: usize) -> (usize, usize, u32) {
+    (index / 81, (index / 9) % 9, (index % 9) as u32 + 1)
+}
+
+// Name a candidate for display, e.g. "5R4C7"
+fn candidate_name(r: usize, c: usize, value: u32) -> String {
+    format!("{}{}", value, square_name(r, c))
+}
+
+// Build the step for a chain, with "=" for strong links and "-" for weak links in the description,
+// e.g. "X-Chain: 5R1C2=5R1C7-5R4C7=5R4C1, removed 5R3C1"
+fn chain_step(name: &str, nodes: &[(usize, usize, u32)], eliminations: Vec<(usize, usize, u32)>) -> SolveStep {
+    let mut chain: String = String::default();
+    for (i, &(r, c, value)) in nodes.iter().enumerate() {
+        if i > 0 {
+            chain += if i % 2 == 1 { "=" } else { "-" };
+        }
+        chain += &candidate_name(r, c, value);
+    }
+    let removed: Vec<String> = eliminations.iter().map(|&(r, c, value)| candidate_name(r, c, value)).collect();
+    let description: String = format!("{}: {}, removed {}", name, chain, removed.join(", "));
+
+    let squares: Vec<(usize, usize)> = nodes.iter().map(|&(r, c, _)| (r, c)).collect();
+    // Longer chains are harder to follow
+    let difficulty: f32 = match name {
+        "X-Chain" => 6.6,
+        "XY-Chain" => 6.7,
+        _ => 7.0,
+    } + 0.1 * (nodes.len().saturating_sub(4) / 4) as f32;
+    SolveStep { eliminations, description, ..SolveStep::default() }.involving(&squares).rated(difficulty.min(7.5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_an_x_chain() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_chain(), "X-Chain", None, &[(5, 1, 9), (7, 0, 9)]);
+    }
+
+    #[test]
+    fn finds_an_xy_chain() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5  7  9 | 48  12  6 | 18  3    24
+             2  1  3 | 9   48  5 | 68  46   7
+             6  8  4 | 7   12  3 | 5   12   9
+             1  4  8 | 6   3   2 | 7   9    5
+             7  6  5 | 1   9   4 | 2   8    3
+             3  9  2 | 5   7   8 | 16  146  46
+             4  3  6 | 2   5   1 | 9   7    8
+             8  2  7 | 3   6   9 | 4   5    1
+             9  5  1 | 48  48  7 | 3   26   26",
+        )
+        .unwrap();
+        assert_step(sudoku.find_chain(), "XY-Chain", None, &[(2, 7, 2), (8, 8, 2)]);
+    }
+
+    #[test]
+    fn finds_an_aic() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "1     27    9   | 5   48  3  | 468  28   267
+             6     48    5   | 7   48  2  | 1    9    3
+             378   2478  23  | 6   1   9  | 48   5    27
+             4     1     7   | 2   3   5  | 9    6    8
+             2     9     8   | 1   6   4  | 3    7    5
+             5     3     6   | 8   9   7  | 2    1    4
+             89    5     4   | 39  2   6  | 7    38   1
+             3789  278   123 | 39  5   18 | 68   4    26
+             38    6     123 | 4   7   18 | 5    238  9",
+        )
+        .unwrap();
+        assert_step(sudoku.find_chain(), "AIC", None, &[(2, 0, 8), (7, 0, 8), (7, 1, 8)]);
+    }
+}
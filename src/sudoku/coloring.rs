@@ -0,0 +1,260 @@
+use super::{sees, squares_to_string, SolveStep, Sudoku};
+
+// A coloring step, whose two color classes the options grid shades in while the step is shown
+#[derive(Debug, Clone)]
+pub struct Coloring {
+    pub name: &'static str,
+    pub value: u32,
+    // The two color classes of the cluster of strong links, exactly one of which holds the value
+    pub colors: [Vec<(usize, usize)>; 2],
+    // The color classes of the second cluster used by Multi-Coloring
+    pub other_colors: Option<[Vec<(usize, usize)>; 2]>,
+    pub eliminations: Vec<(usize, usize)>,
+}
+
+impl Coloring {
+    // Describe the coloring for display, e.g. "Color Trap: 5 colored R1C1, R4C2 / R1C6, removed from R4C6"
+    pub fn description(&self) -> String {
+        let mut clusters: Vec<String> =
+            vec![format!("{} / {}", squares_to_string(&self.colors[0]), squares_to_string(&self.colors[1]))];
+        if let Some(other) = &self.other_colors {
+            clusters.push(format!("{} / {}", squares_to_string(&other[0]), squares_to_string(&other[1])));
+        }
+        format!(
+            "{}: {} colored {}, removed from {}",
+            self.name,
+            self.value,
+            clusters.join(" and "),
+            squares_to_string(&self.eliminations)
+        )
+    }
+
+    // Find which color class a square belongs to: 0 and 1 for the first cluster, 2 and 3 for the second
+    pub fn color_of(&self, r: usize, c: usize) -> Option<usize> {
+        let mut classes: Vec<&Vec<(usize, usize)>> = self.colors.iter().collect();
+        if let Some(other) = &self.other_colors {
+            classes.extend(other.iter());
+        }
+        classes.iter().position(|class| class.contains(&(r, c)))
+    }
+}
+
+impl Sudoku {
+    // Find the first coloring as a solver step, keeping the coloring to show as the hint
+    pub fn coloring_step(&self) -> Option<SolveStep> {
+        self.find_coloring().map(|coloring| {
+            let mut squares: Vec<(usize, usize)> = coloring.colors.concat();
+            if let Some(other) = &coloring.other_colors {
+                squares.extend(other.concat());
+            }
+            let step = SolveStep::remove(coloring.value, &coloring.eliminations, coloring.description());
+            SolveStep { coloring: Some(coloring), ..step.involving(&squares) }
+        })
+    }
+
+    // Find the first Color Wrap, Color Trap, or Multi-Coloring which removes at least one option
+    pub fn find_coloring(&self) -> Option<Coloring> {
+        for value in 1..=9u32 {
+            let clusters: Vec<[Vec<(usize, usize)>; 2]> = self.color_clusters(value);
+            for colors in &clusters {
+                if let Some(coloring) = self.find_simple_coloring(value, colors) {
+                    return Some(coloring);
+                }
+            }
+            for (i, colors) in clusters.iter().enumerate() {
+                for (j, other) in clusters.iter().enumerate() {
+                    if i != j {
+                        if let Some(coloring) = self.find_multi_coloring(value, colors, other) {
+                            return Some(coloring);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Simple coloring within one cluster. Color Wrap: two squares of the same color see each
+    // other, so that color can't hold the value and it is removed from all of them. Color Trap:
+    // a square outside the cluster sees both colors, one of which holds the value, so it is removed
+    fn find_simple_coloring(&self, value: u32, colors: &[Vec<(usize, usize)>; 2]) -> Option<Coloring> {
+        for color in 0..2 {
+            let class: &Vec<(usize, usize)> = &colors[color];
+            if class.iter().any(|&a| class.iter().any(|&b| sees(a, b))) {
+                return Some(Coloring {
+                    name: "Color Wrap",
+                    value,
+                    colors: colors.clone(),
+                    other_colors: None,
+                    eliminations: class.clone(),
+                });
+            }
+        }
+
+        let eliminations: Vec<(usize, usize)> = self.squares_seeing_both(value, &colors[0], &colors[1]);
+        if !eliminations.is_empty() {
+            return Some(Coloring {
+                name: "Color Trap",
+                value,
+                colors: colors.clone(),
+                other_colors: None,
+                eliminations,
+            });
+        }
+        None
+    }
+
+    // Multi-Coloring across two clusters. When color A1 sees both colors of the other cluster,
+    // A1 can't hold the value and it is removed from all of A1. When A1 only sees color B1, they
+    // can't both hold the value, so one of A2 and B2 must and it is removed from squares seeing both
+    fn find_multi_coloring(
+        &self,
+        value: u32,
+        colors: &[Vec<(usize, usize)>; 2],
+        other: &[Vec<(usize, usize)>; 2],
+    ) -> Option<Coloring> {
+        let class_sees = |a: &Vec<(usize, usize)>, b: &Vec<(usize, usize)>| {
+            a.iter().any(|&x| b.iter().any(|&y| sees(x, y)))
+        };
+
+        for color in 0..2 {
+            let (a1, a2) = (&colors[color], &colors[1 - color]);
+            let mut eliminations: Vec<(usize, usize)> = vec![];
+            if class_sees(a1, &other[0]) && class_sees(a1, &other[1]) {
+                eliminations = a1.clone();
+            } else {
+                for other_color in 0..2 {
+                    if class_sees(a1, &other[other_color]) {
+                        eliminations = self.squares_seeing_both(value, a2, &other[1 - other_color]);
+                        break;
+                    }
+                }
+            }
+
+            if !eliminations.is_empty() {
+                return Some(Coloring {
+                    name: "Multi-Coloring",
+                    value,
+                    colors: colors.clone(),
+                    other_colors: Some(other.clone()),
+                    eliminations,
+                });
+            }
+        }
+        None
+    }
+
+    // Split the strong links of a value into connected clusters, coloring the squares of each
+    // cluster with two alternating colors
+    fn color_clusters(&self, value: u32) -> Vec<[Vec<(usize, usize)>; 2]> {
+        let links: Vec<[(usize, usize); 2]> = (0..27).filter_map(|unit| self.strong_link(unit, value)).collect();
+
+        let mut clusters: Vec<[Vec<(usize, usize)>; 2]> = vec![];
+        let mut colored: Vec<(usize, usize)> = vec![];
+        for link in &links {
+            if colored.contains(&link[0]) {
+                continue;
+            }
+
+            // Walk out from the link, giving each newly reached square the opposite color
+            let mut colors: [Vec<(usize, usize)>; 2] = [vec![link[0]], vec![]];
+            let mut queue: Vec<((usize, usize), usize)> = vec![(link[0], 0)];
+            colored.push(link[0]);
+            while let Some((square, color)) = queue.pop() {
+                for &[a, b] in &links {
+                    let next = if a == square {
+                        b
+                    } else if b == square {
+                        a
+                    } else {
+                        continue;
+                    };
+                    if !colored.contains(&next) {
+                        colored.push(next);
+                        colors[1 - color].push(next);
+                        queue.push((next, 1 - color));
+                    }
+                }
+            }
+            clusters.push(colors);
+        }
+        clusters
+    }
+
+    // List the empty squares outside of both classes which have the value and see a square of each
+    fn squares_seeing_both(&self, value: u32, a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut seeing: Vec<(usize, usize)> = vec![];
+        for r in 0..9 {
+            for c in 0..9 {
+                let square = (r, c);
+                if self.grid[r][c].value.is_none()
+                    && self.grid[r][c].options.contains(&value)
+                    && !a.contains(&square)
+                    && !b.contains(&square)
+                    && a.iter().any(|&x| sees(x, square))
+                    && b.iter().any(|&y| sees(y, square))
+                {
+                    seeing.push(square);
+                }
+            }
+        }
+        seeing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_a_color_trap() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3   | 6  4  7
+             8    1   6 | 4   9  7   | 3  2  5
+             4    7   3 | 5   6  2   | 1  9  8
+             3    4   7 | 8   2  16  | 9  5  16
+             19   8   2 | 69  4  5   | 7  3  16
+             169  69  5 | 3   7  169 | 4  8  2
+             7    3   4 | 2   1  8   | 5  6  9
+             69   5   8 | 7   3  69  | 2  1  4
+             2    69  1 | 69  5  4   | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.coloring_step(), "Color Trap", None, &[(5, 5, 6)]);
+    }
+
+    #[test]
+    fn finds_a_color_wrap() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.coloring_step(), "Color Wrap", None, &[(4, 0, 9), (8, 3, 9), (5, 5, 9), (7, 0, 9), (5, 1, 9)]);
+    }
+
+    #[test]
+    fn finds_multi_coloring() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "6  8   7 | 2    3    4  | 1  9   5
+             9  4   2 | 8    1    5  | 7  3   6
+             1  3   5 | 67   67   9  | 2  4   8
+             7  6   1 | 3    5    2  | 9  8   4
+             2  5   3 | 4    9    8  | 6  1   7
+             4  9   8 | 1    67   67 | 5  2   3
+             5  1   4 | 679  8    67 | 3  67  29
+             8  27  9 | 5    267  3  | 4  67  12
+             3  27  6 | 79   247  1  | 8  57  29",
+        )
+        .unwrap();
+        assert_step(sudoku.coloring_step(), "Multi-Coloring", None, &[(8, 8, 2)]);
+    }
+}
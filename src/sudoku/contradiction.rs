@@ -0,0 +1,150 @@
+use super::{square_name, squares_to_string, unit_name, unit_squares, Sudoku};
+
+// Something in the grid which breaks the rules, so the puzzle can't be solved from here
+#[derive(Debug, Clone)]
+pub enum Contradiction {
+    // A value placed more than once in a row, column, or box
+    Duplicate { unit: usize, value: u32, squares: Vec<(usize, usize)> },
+    // An empty square with no options left
+    NoOptions(usize, usize),
+    // A value missing from a row, column, or box with no square left to go in
+    NoSquare { unit: usize, value: u32 },
+}
+
+impl Contradiction {
+    // Describe the contradiction for display, e.g. "R4C7 has no candidates"
+    pub fn description(&self) -> String {
+        match self {
+            Contradiction::Duplicate { unit, value, squares } => {
+                format!("{} repeated in {} at {}", value, unit_name(*unit), squares_to_string(squares))
+            }
+            Contradiction::NoOptions(r, c) => format!("{} has no candidates", square_name(*r, *c)),
+            Contradiction::NoSquare { unit, value } => {
+                format!("{} has no place left in {}", value, unit_name(*unit))
+            }
+        }
+    }
+
+    // List the squares which break the rules, to point them out in the grid
+    pub fn squares(&self) -> Vec<(usize, usize)> {
+        match self {
+            Contradiction::Duplicate { squares, .. } => squares.clone(),
+            Contradiction::NoOptions(r, c) => vec![(*r, *c)],
+            Contradiction::NoSquare { unit, .. } => unit_squares(*unit),
+        }
+    }
+}
+
+impl Sudoku {
+    // Find the first contradiction in the grid. Options are only checked once they are up to date,
+    // since before then an empty list just means they haven't been worked out yet
+    pub fn find_contradiction(&self) -> Option<Contradiction> {
+        if let Some(duplicate) = self.find_duplicate() {
+            return Some(duplicate);
+        }
+        if !self.options_ready {
+            return None;
+        }
+
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_none() && self.grid[r][c].options.is_empty() {
+                    return Some(Contradiction::NoOptions(r, c));
+                }
+            }
+        }
+        for unit in 0..27 {
+            let squares: Vec<(usize, usize)> = unit_squares(unit);
+            for value in 1..=9u32 {
+                if !squares.iter().any(|&(r, c)| self.grid[r][c].value == Some(value))
+                    && self.option_squares(&squares, value).is_empty()
+                {
+                    return Some(Contradiction::NoSquare { unit, value });
+                }
+            }
+        }
+        None
+    }
+
+    // Find the first value placed more than once in a row, column, or box
+    pub fn find_duplicate(&self) -> Option<Contradiction> {
+        self.find_duplicates().into_iter().next()
+    }
+
+    // List every value placed more than once in a row, column, or box
+    pub fn find_duplicates(&self) -> Vec<Contradiction> {
+        let mut duplicates: Vec<Contradiction> = vec![];
+        for unit in 0..27 {
+            let squares: Vec<(usize, usize)> = unit_squares(unit);
+            for value in 1..=9u32 {
+                let placed: Vec<(usize, usize)> =
+                    squares.iter().filter(|&&(r, c)| self.grid[r][c].value == Some(value)).copied().collect();
+                if placed.len() > 1 {
+                    duplicates.push(Contradiction::Duplicate { unit, value, squares: placed });
+                }
+            }
+        }
+        duplicates
+    }
+
+    // List the squares holding a value which is repeated in one of their units
+    pub fn conflicting_squares(&self) -> Vec<(usize, usize)> {
+        let mut squares: Vec<(usize, usize)> = self.find_duplicates().iter().flat_map(Contradiction::squares).collect();
+        squares.sort();
+        squares.dedup();
+        squares
+    }
+
+    // Describe the repeated value which the square is part of, if any, to show after an edit
+    pub fn conflict_at(&self, r: usize, c: usize) -> Option<String> {
+        self.find_duplicates()
+            .into_iter()
+            .find(|duplicate| duplicate.squares().contains(&(r, c)))
+            .map(|duplicate| format!("Conflict: {}", duplicate.description()))
+    }
+
+    // Show the contradiction as the status if there is one, returning whether the solve must stop
+    pub fn report_contradiction(&mut self) -> bool {
+        match self.find_contradiction() {
+            Some(contradiction) => {
+                self.solved = false;
+                self.status = format!("Contradiction: {}", contradiction.description());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Solve a grid given row by row in the one line format, checking that it stops straight away with the status
+    fn assert_solve_stops(rows: [&str; 9], status: &str) {
+        let mut sudoku: Sudoku = Sudoku::from_line(&rows.concat()).unwrap();
+        let steps = sudoku.solve(false);
+        assert!(steps.is_empty());
+        assert!(!sudoku.solved);
+        assert_eq!(sudoku.status, status);
+    }
+
+    #[test]
+    fn solve_stops_on_a_repeated_value() {
+        let rows = ["55.......", ".........", ".........", ".........", ".........", ".........", ".........", ".........", "........."];
+        assert_solve_stops(rows, "Contradiction: 5 repeated in row 1 at R1C1, R1C2");
+    }
+
+    #[test]
+    fn solve_stops_on_a_square_with_no_options() {
+        let rows = ["......9..", ".........", ".........", "123456.78", ".........", ".........", ".........", ".........", "........."];
+        assert_solve_stops(rows, "Contradiction: R4C7 has no candidates");
+    }
+
+    #[test]
+    fn solve_stops_on_a_value_with_no_square() {
+        // Every empty square of row 1 sees a 1 elsewhere
+        let rows = ["........2", "1........", "...1.....", "......1..", ".........", ".........", ".......1.", ".........", "........."];
+        assert_solve_stops(rows, "Contradiction: 1 has no place left in row 1");
+    }
+}
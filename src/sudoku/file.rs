@@ -0,0 +1,321 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{vec_to_string, Sudoku, SudokuSquare};
+
+// The first line of a saved puzzle, so other files aren't mistaken for one
+const FILE_HEADER: &str = "sudoku-solver 1";
+
+impl Sudoku {
+    // Save the grid to a file, keeping the given squares, the values filled in since, and the options
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_file_string())
+    }
+
+    // Load a grid saved by save, with default settings
+    pub fn load(path: &Path) -> io::Result<Sudoku> {
+        let contents: String = fs::read_to_string(path)?;
+        Sudoku::from_file_string(&contents).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    // Read a puzzle from the one line format used to share puzzles: 81 characters row by row, with
+    // "0" or "." for an empty square. Every value is a given, and whitespace is skipped
+    pub fn from_line(line: &str) -> Result<Sudoku, String> {
+        let values: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+        if values.len() != 81 {
+            return Err(format!("expected 81 squares but found {}", values.len()));
+        }
+        let mut grid: Vec<Vec<SudokuSquare>> = vec![vec![SudokuSquare::default(); 9]; 9];
+        for (i, &c) in values.iter().enumerate() {
+            let value: Option<u32> = parse_value(c)?;
+            grid[i / 9][i % 9] = SudokuSquare { value, options: Vec::default(), given: value.is_some() };
+        }
+        Ok(Sudoku::new(grid))
+    }
+
+    // Write the values of the grid in the one line format, with "." for an empty square
+    pub fn to_line(&self) -> String {
+        self.grid.iter().flatten().map(value_char).collect()
+    }
+
+    // Read a grid from the pencil-mark format used on solver forums, where each square shows its
+    // options and the borders are ignored. A square with one option is read as a placed value, which
+    // isn't a given, since the format doesn't say which values were given. A "0" is an empty square
+    // with no options left
+    pub fn from_pencil_marks(text: &str) -> Result<Sudoku, String> {
+        let squares: Vec<&str> = text.split(|c: char| !c.is_ascii_digit()).filter(|square| !square.is_empty()).collect();
+        if squares.len() != 81 {
+            return Err(format!("expected 81 squares but found {}", squares.len()));
+        }
+        let mut grid: Vec<Vec<SudokuSquare>> = vec![vec![SudokuSquare::default(); 9]; 9];
+        for (i, square) in squares.iter().enumerate() {
+            if *square == "0" {
+                continue;
+            }
+            let mut options: Vec<u32> = vec![];
+            for c in square.chars() {
+                let option: u32 = parse_value(c)?.ok_or_else(|| format!("unexpected option 0 in {}", square))?;
+                if !options.contains(&option) {
+                    options.push(option);
+                }
+            }
+            options.sort();
+            grid[i / 9][i % 9] = match options[..] {
+                [value] => SudokuSquare { value: Some(value), options: Vec::default(), given: false },
+                _ => SudokuSquare { value: None, options, given: false },
+            };
+        }
+
+        let mut sudoku: Sudoku = Sudoku::new(grid);
+        // The options were worked out by whoever shared the grid, so the solver carries on from them,
+        // once the placed values are taken out of the options of the squares they see
+        for (r, c) in (0..81).map(|i| (i / 9, i % 9)) {
+            if let Some(value) = sudoku.grid[r][c].value {
+                sudoku.remove_peer_options(r, c, value);
+            }
+        }
+        sudoku.options_ready = true;
+        Ok(sudoku)
+    }
+
+    // Write the grid in the pencil-mark format, showing the value of each filled square and the
+    // options of each empty one, or "0" if it has none, lined up in columns with borders around the boxes
+    pub fn to_pencil_marks(&self) -> String {
+        let mut sudoku: Sudoku = self.clone();
+        if !sudoku.options_ready {
+            sudoku.update_all_options();
+        }
+        let cells: Vec<Vec<String>> = sudoku
+            .grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|square| match square.value {
+                        Some(value) => value.to_string(),
+                        None if square.options.is_empty() => "0".to_string(),
+                        None => vec_to_string(&square.options),
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = (0..9).map(|c| cells.iter().map(|row| row[c].len()).max().unwrap_or(1)).collect();
+        // Each box of a row is its squares padded to their column's width, two spaces apart
+        let box_width = |b: usize| (b * 3..b * 3 + 3).map(|c| widths[c]).sum::<usize>() + 4;
+        let border = |corner: char, middle: char| {
+            let dashes: Vec<String> = (0..3).map(|b| "-".repeat(box_width(b) + 2)).collect();
+            format!("{}{}{}\n", corner, dashes.join(&middle.to_string()), corner)
+        };
+
+        let mut text: String = border('.', '.');
+        for (r, row) in cells.iter().enumerate() {
+            if r == 3 || r == 6 {
+                text += &border(':', '+');
+            }
+            let boxes: Vec<String> = (0..3)
+                .map(|b| {
+                    let squares: Vec<String> =
+                        (b * 3..b * 3 + 3).map(|c| format!("{:width$}", row[c], width = widths[c])).collect();
+                    squares.join("  ")
+                })
+                .collect();
+            text += &format!("| {} |\n", boxes.join(" | "));
+        }
+        text + &border('\'', '\'')
+    }
+
+    // Read a puzzle in either sharing format, telling them apart by the pencil-mark grid taking up
+    // several lines
+    pub fn from_text(text: &str) -> Result<Sudoku, String> {
+        if text.trim().contains('\n') {
+            Sudoku::from_pencil_marks(text)
+        } else {
+            Sudoku::from_line(text)
+        }
+    }
+
+    // Write the grid as lines of text: the given squares, all the values, and each square's options,
+    // with "." for an empty square or a square without options. The options are only written while
+    // they are up to date, since after a hand edit they no longer match the grid
+    fn to_file_string(&self) -> String {
+        let squares = || self.grid.iter().flatten();
+        let givens: String = squares().map(|square| if square.given { value_char(square) } else { '.' }).collect();
+        let mut contents: String = format!("{}\ngivens: {}\nvalues: {}\n", FILE_HEADER, givens, self.to_line());
+        if self.options_ready {
+            let options: Vec<String> = squares()
+                .map(|square| if square.options.is_empty() { ".".to_string() } else { vec_to_string(&square.options) })
+                .collect();
+            contents += &format!("options: {}\n", options.join(" "));
+        }
+        contents
+    }
+
+    // Read a grid written by to_file_string
+    fn from_file_string(contents: &str) -> Result<Sudoku, String> {
+        let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some(FILE_HEADER) {
+            return Err("not a saved Sudoku puzzle".to_string());
+        }
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|line| line.strip_prefix(':'))
+                .map(str::trim)
+                .ok_or(format!("missing {}", name))
+        };
+        let givens: Vec<char> = field("givens")?.chars().collect();
+        let values: Vec<char> = field("values")?.chars().collect();
+        // Without saved options they are listed again from scratch on the next solve
+        let options: Option<Vec<&str>> = match lines.next() {
+            Some(line) => {
+                let line: &str = line.strip_prefix("options:").ok_or(format!("unexpected line {}", line))?;
+                Some(line.split_whitespace().collect())
+            }
+            None => None,
+        };
+        if givens.len() != 81 || values.len() != 81 || options.as_ref().is_some_and(|options| options.len() != 81) {
+            return Err("expected 81 squares on each line".to_string());
+        }
+
+        let mut grid: Vec<Vec<SudokuSquare>> = vec![vec![SudokuSquare::default(); 9]; 9];
+        for i in 0..81 {
+            let square: &mut SudokuSquare = &mut grid[i / 9][i % 9];
+            let given: Option<u32> = parse_value(givens[i])?;
+            square.value = parse_value(values[i])?;
+            if given.is_some() && given != square.value {
+                return Err(format!("given and value differ at square {}", i + 1));
+            }
+            square.given = given.is_some();
+            if let Some(options) = options.as_ref().filter(|options| options[i] != ".") {
+                square.options = options[i]
+                    .chars()
+                    .map(|c| parse_value(c)?.ok_or_else(|| "empty option".to_string()))
+                    .collect::<Result<Vec<u32>, String>>()?;
+            }
+        }
+
+        let mut sudoku: Sudoku = Sudoku::new(grid);
+        // Saved options carry on from where the solver left off
+        sudoku.options_ready = options.is_some();
+        Ok(sudoku)
+    }
+}
+
+// Show a square's value as one character, with "." for an empty square
+fn value_char(square: &SudokuSquare) -> char {
+    square.value.and_then(|value| char::from_digit(value, 10)).unwrap_or('.')
+}
+
+// Read a value from one character, where "." or "0" is an empty square
+fn parse_value(c: char) -> Result<Option<u32>, String> {
+    match c {
+        '.' | '0' => Ok(None),
+        '1'..='9' => Ok(c.to_digit(10)),
+        _ => Err(format!("unexpected character '{}'", c)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{empty_board, med_board_1, sees};
+    use super::*;
+
+    // Save the grid to a file in the temporary directory and load it back
+    fn round_trip(sudoku: &Sudoku, name: &str) -> Sudoku {
+        let path = std::env::temp_dir().join(format!("sudoku-solver-{}-{}.txt", name, std::process::id()));
+        sudoku.save(&path).unwrap();
+        let loaded: Sudoku = Sudoku::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn save_and_load_keep_the_grid_and_options() {
+        let mut sudoku: Sudoku = Sudoku::new(med_board_1());
+        sudoku.solve(true);
+        sudoku.solve(true);
+
+        let loaded: Sudoku = round_trip(&sudoku, "options");
+        assert_eq!(loaded.grid, sudoku.grid);
+        assert!(loaded.options_ready);
+    }
+
+    #[test]
+    fn stale_options_are_not_loaded_after_a_hand_edit() {
+        let mut sudoku: Sudoku = Sudoku::new(med_board_1());
+        sudoku.solve(true);
+
+        // Type a value into an empty square and delete it again. The grid's square ids give the row first
+        let (r, c) = (0..81).map(|i| (i / 9, i % 9)).find(|&(r, c)| sudoku.grid[r][c].value.is_none()).unwrap();
+        let id: String = format!("square-C{}-R{}", r, c);
+        sudoku.square_text_update(&id, &sudoku.grid[r][c].options[0].to_string());
+        sudoku.square_text_update(&id, "");
+        assert_eq!(sudoku.grid[r][c].value, None);
+
+        let mut loaded: Sudoku = round_trip(&sudoku, "stale");
+        assert!(!loaded.options_ready);
+        loaded.solve(false);
+        assert!(loaded.solved, "{}", loaded.status);
+    }
+
+    #[test]
+    fn pencil_marks_keep_values_and_options() {
+        let mut sudoku: Sudoku = Sudoku::new(med_board_1());
+        sudoku.solve(true);
+        sudoku.solve(true);
+        let pencil_marks: String = sudoku.to_pencil_marks();
+
+        let loaded: Sudoku = Sudoku::from_text(&pencil_marks).unwrap();
+        assert!(loaded.options_ready);
+        for (r, c) in (0..81).map(|i| (i / 9, i % 9)) {
+            // Placed values can still be cleared, and a square with one option left is read as placed
+            let (square, loaded_square) = (&sudoku.grid[r][c], &loaded.grid[r][c]);
+            assert!(!loaded_square.given);
+            match (square.value, &square.options[..]) {
+                (Some(value), _) | (None, &[value]) => assert_eq!(loaded_square.value, Some(value)),
+                (None, options) => {
+                    // Less any value placed in a square it sees
+                    let placed = |value: &u32| {
+                        (0..81).any(|i| sees((r, c), (i / 9, i % 9)) && loaded.grid[i / 9][i % 9].value == Some(*value))
+                    };
+                    let options: Vec<u32> = options.iter().filter(|value| !placed(value)).copied().collect();
+                    assert_eq!(loaded_square.value, None);
+                    assert_eq!(loaded_square.options, options);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pencil_marks_take_placed_values_out_of_peer_options() {
+        let mut sudoku: Sudoku = Sudoku::new(empty_board());
+        sudoku.update_all_options();
+        sudoku.grid[0][0].options = vec![5];
+        sudoku.grid[8][8].options = vec![];
+
+        let loaded: Sudoku = Sudoku::from_pencil_marks(&sudoku.to_pencil_marks()).unwrap();
+        assert_eq!(loaded.grid[0][0].value, Some(5));
+        for (r, c) in [(0, 8), (8, 0), (2, 2)] {
+            assert!(!loaded.grid[r][c].options.contains(&5));
+        }
+        // A square with no options left still reads back, so the solver can report it
+        assert_eq!(loaded.grid[8][8], SudokuSquare::default());
+        assert!(loaded.find_contradiction().is_some());
+    }
+
+    #[test]
+    fn line_format_round_trip() {
+        let line: &str = "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+        let sudoku: Sudoku = Sudoku::from_text(&line.replace('.', "0")).unwrap();
+        assert_eq!(sudoku.to_line(), line);
+        assert!(sudoku.grid[0][0].given);
+        assert!(Sudoku::from_line("123").is_err());
+    }
+
+    #[test]
+    fn load_rejects_other_files() {
+        assert!(Sudoku::from_file_string("hello").is_err());
+        assert!(Sudoku::from_file_string(&format!("{}\ngivens: 123\nvalues: 123\n", FILE_HEADER)).is_err());
+    }
+}
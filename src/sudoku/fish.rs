@@ -0,0 +1,304 @@
+use super::{combinations, squares_to_string, unit_squares, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Basic fish: when a value's options in N rows all lie in the same N columns, each of those
+    // columns must take the value in one of the rows, so it can be removed from the rest of the
+    // columns (and the same with rows and columns swapped). N = 2, 3, 4 are the X-Wing,
+    // Swordfish, and Jellyfish
+    pub fn find_basic_fish(&self) -> Option<SolveStep> {
+        for size in 2..=4usize {
+            for value in 1..=9u32 {
+                for row_based in [true, false] {
+                    if let Some(step) = self.find_fish(value, size, row_based) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Look for a fish of one size for one value, using rows or columns as the base lines
+    fn find_fish(&self, value: u32, size: usize, row_based: bool) -> Option<SolveStep> {
+        // Base lines are the rows (or columns) with between 2 and N options for the value
+        let base_lines: Vec<usize> = (0..9usize)
+            .filter(|&line| {
+                let spots = self.line_spots(line, value, row_based).len();
+                spots >= 2 && spots <= size
+            })
+            .collect();
+
+        for base in combinations(&base_lines, size) {
+            // Cover lines are the columns (or rows) crossing the base lines' options
+            let mut cover: Vec<usize> = vec![];
+            for &line in &base {
+                for spot in self.line_spots(line, value, row_based) {
+                    if !cover.contains(&spot) {
+                        cover.push(spot);
+                    }
+                }
+            }
+            if cover.len() != size {
+                continue;
+            }
+            cover.sort();
+
+            // Remove the value from the cover lines outside of the base lines
+            let mut removed: Vec<(usize, usize)> = vec![];
+            for &cover_line in &cover {
+                for line in 0..9usize {
+                    if base.contains(&line) {
+                        continue;
+                    }
+                    let (r, c) = if row_based { (line, cover_line) } else { (cover_line, line) };
+                    if self.grid[r][c].value.is_none() && self.grid[r][c].options.contains(&value) {
+                        removed.push((r, c));
+                    }
+                }
+            }
+
+            if !removed.is_empty() {
+                let (base_name, cover_name) = if row_based { ("rows", "columns") } else { ("columns", "rows") };
+                let description: String = format!(
+                    "{}: {} in {} {} and {} {}, removed from {}",
+                    fish_name(size),
+                    value,
+                    base_name,
+                    lines_to_string(&base),
+                    cover_name,
+                    lines_to_string(&cover),
+                    squares_to_string(&removed)
+                );
+                let step = SolveStep::remove(value, &removed, description);
+                return Some(step.involving(&self.fish_squares(&base, value, row_based)).rated(fish_difficulty(size)));
+            }
+        }
+        None
+    }
+
+    // Finned and sashimi fish: a fish whose base lines have extra options ("fins") all in one
+    // 3x3 box. Either one of the fins holds the value or the fish is complete, so any square
+    // which the fish would eliminate and which also sees every fin can have the value removed.
+    // The fish is sashimi when a base line would be left with fewer than 2 options without the fins
+    pub fn find_finned_fish(&self) -> Option<SolveStep> {
+        for size in 2..=4usize {
+            for value in 1..=9u32 {
+                for row_based in [true, false] {
+                    if let Some(step) = self.find_finned(value, size, row_based) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Look for a finned fish of one size for one value, using rows or columns as the base lines
+    fn find_finned(&self, value: u32, size: usize, row_based: bool) -> Option<SolveStep> {
+        let spots: Vec<Vec<usize>> = (0..9usize).map(|line| self.line_spots(line, value, row_based)).collect();
+        let base_lines: Vec<usize> = (0..9usize).filter(|&line| !spots[line].is_empty()).collect();
+
+        for base in combinations(&base_lines, size) {
+            let mut crossing: Vec<usize> = base.iter().flat_map(|&line| spots[line].clone()).collect();
+            crossing.sort();
+            crossing.dedup();
+            if crossing.len() <= size {
+                // Without any fins left over this is a basic fish
+                continue;
+            }
+
+            for cover in combinations(&crossing, size) {
+                // The fins are the base options outside of the cover lines, and must share a box
+                let mut fins: Vec<(usize, usize)> = vec![];
+                for &line in &base {
+                    for &spot in &spots[line] {
+                        if !cover.contains(&spot) {
+                            fins.push(if row_based { (line, spot) } else { (spot, line) });
+                        }
+                    }
+                }
+                let (fin_r, fin_c) = fins[0];
+                let fin_box: usize = (fin_r / 3) * 3 + fin_c / 3;
+                if !fins.iter().all(|&(r, c)| (r / 3) * 3 + c / 3 == fin_box) {
+                    continue;
+                }
+
+                // Remove the value from the cover lines outside of the base lines, within the fin box
+                let mut removed: Vec<(usize, usize)> = vec![];
+                for &cover_line in &cover {
+                    for line in 0..9usize {
+                        let (r, c) = if row_based { (line, cover_line) } else { (cover_line, line) };
+                        if base.contains(&line) || (r / 3) * 3 + c / 3 != fin_box {
+                            continue;
+                        }
+                        if self.grid[r][c].value.is_none() && self.grid[r][c].options.contains(&value) {
+                            removed.push((r, c));
+                        }
+                    }
+                }
+
+                if !removed.is_empty() {
+                    let sashimi: bool = base
+                        .iter()
+                        .any(|&line| spots[line].iter().filter(|spot| cover.contains(spot)).count() < 2);
+                    let (base_name, cover_name) = if row_based { ("rows", "columns") } else { ("columns", "rows") };
+                    let description: String = format!(
+                        "{} {}: {} in {} {} and {} {} with fins at {}, removed from {}",
+                        if sashimi { "Sashimi" } else { "Finned" },
+                        fish_name(size),
+                        value,
+                        base_name,
+                        lines_to_string(&base),
+                        cover_name,
+                        lines_to_string(&cover),
+                        squares_to_string(&fins),
+                        squares_to_string(&removed)
+                    );
+                    // The fins make a fish a little harder to spot
+                    let step = SolveStep::remove(value, &removed, description);
+                    return Some(step.involving(&self.fish_squares(&base, value, row_based)).rated(fish_difficulty(size) + 0.2));
+                }
+            }
+        }
+        None
+    }
+
+    // List the squares of the base lines where the value is still an option, including any fins
+    fn fish_squares(&self, base: &[usize], value: u32, row_based: bool) -> Vec<(usize, usize)> {
+        base.iter()
+            .flat_map(|&line| {
+                let spots: Vec<usize> = self.line_spots(line, value, row_based);
+                spots.into_iter().map(move |spot| if row_based { (line, spot) } else { (spot, line) })
+            })
+            .collect()
+    }
+
+    // List the positions along a row (or column) where the value is still an option
+    fn line_spots(&self, line: usize, value: u32, row_based: bool) -> Vec<usize> {
+        let unit: usize = if row_based { line } else { 9 + line };
+        unit_squares(unit)
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, (r, c))| self.grid[r][c].value.is_none() && self.grid[r][c].options.contains(&value))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+// Name a fish size for display
+fn fish_name(size: usize) -> &'static str {
+    match size {
+        2 => "X-Wing",
+        3 => "Swordfish",
+        _ => "Jellyfish",
+    }
+}
+
+// How hard a fish of the size is to spot
+fn fish_difficulty(size: usize) -> f32 {
+    match size {
+        2 => 3.2,
+        3 => 3.8,
+        _ => 5.2,
+    }
+}
+
+// Helper function to print out a list of 0-based line indices as 1-based numbers, e.g. "2, 6"
+fn lines_to_string(lines: &[usize]) -> String {
+    lines
+        .iter()
+        .map(|line| (line + 1).to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_an_x_wing() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "9   7   1  | 8  3   5  | 2  6   4
+             38  36  68 | 1  4   2  | 7  5   9
+             2   4   5  | 6  7   9  | 8  1   3
+             4   8   9  | 5  2   3  | 6  7   1
+             6   5   3  | 7  8   1  | 4  9   2
+             7   1   2  | 4  9   6  | 3  8   5
+             1   36  7  | 2  56  48 | 9  34  68
+             58  9   4  | 3  56  7  | 1  2   68
+             38  2   68 | 9  1   48 | 5  34  7",
+        )
+        .unwrap();
+        assert_step(sudoku.find_basic_fish(), "X-Wing", None, &[(6, 1, 6)]);
+    }
+
+    #[test]
+    fn finds_a_swordfish() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "7  3   2  | 8   16   16 | 4  9  5
+             8  6   1  | 5   4    9  | 7  2  3
+             4  9   5  | 7   3    2  | 8  6  1
+             9  58  78 | 2   57   4  | 3  1  6
+             6  14  47 | 3   9    17 | 2  5  8
+             2  15  3  | 16  156  8  | 9  7  4
+             1  7   9  | 4   8    5  | 6  3  2
+             3  24  46 | 16  267  17 | 5  8  9
+             5  28  68 | 9   26   3  | 1  4  7",
+        )
+        .unwrap();
+        assert_step(sudoku.find_basic_fish(), "Swordfish", None, &[(0, 4, 6)]);
+    }
+
+    #[test]
+    fn finds_a_jellyfish() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    9    18 | 17  17   3  | 4  2  6
+             3    2    6  | 8   4    5  | 7  1  9
+             17   17   4  | 9   6    2  | 5  3  8
+             6    178  5  | 17  9    18 | 2  4  3
+             128  4    18 | 3   5    18 | 6  9  7
+             27   3    9  | 6   27   4  | 8  5  1
+             18   5    3  | 12  128  7  | 9  6  4
+             4    6    7  | 5   3    9  | 1  8  2
+             9    18   2  | 4   18   6  | 3  7  5",
+        )
+        .unwrap();
+        assert_step(sudoku.find_basic_fish(), "Jellyfish", None, &[(4, 2, 8)]);
+    }
+
+    #[test]
+    fn finds_a_finned_swordfish() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "4  6  2  | 3  7   89 | 19  5   18
+             8  9  7  | 5  2   1  | 3   6   4
+             3  1  5  | 4  6   89 | 79  2   78
+             5  3  9  | 2  8   6  | 4   17  17
+             1  2  8  | 7  5   4  | 6   3   9
+             7  4  6  | 9  1   3  | 2   8   5
+             9  5  1  | 6  3   7  | 8   4   2
+             6  7  34 | 8  49  29 | 5   19  13
+             2  8  34 | 1  49  5  | 79  79  367",
+        )
+        .unwrap();
+        assert_step(sudoku.find_finned_fish(), "Finned Swordfish", None, &[(8, 6, 9)]);
+    }
+
+    #[test]
+    fn finds_a_sashimi_swordfish() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "9   7   1  | 8  3   5  | 2  6   4
+             38  36  68 | 1  4   2  | 7  5   9
+             2   4   5  | 6  7   9  | 8  1   3
+             4   8   9  | 5  2   3  | 6  7   1
+             6   5   3  | 7  8   1  | 4  9   2
+             7   1   2  | 4  9   6  | 3  8   5
+             1   36  7  | 2  56  48 | 9  34  68
+             58  9   4  | 3  56  7  | 1  2   68
+             38  2   68 | 9  1   48 | 5  34  7",
+        )
+        .unwrap();
+        assert_step(sudoku.find_finned_fish(), "Sashimi Swordfish", None, &[(6, 1, 6)]);
+    }
+}
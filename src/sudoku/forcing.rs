@@ -0,0 +1,250 @@
+use super::{square_name, unit_name, unit_squares, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Find the first Nishio, Cell Forcing Chain, or Region Forcing Chain which makes progress. Each
+    // of these assumes a value and follows the singles it leads to, so it can be explained as a
+    // proof rather than a guess
+    pub fn find_forcing(&self) -> Option<SolveStep> {
+        self.find_nishio()
+            .or_else(|| self.find_cell_forcing())
+            .or_else(|| self.find_region_forcing())
+    }
+
+    // Nishio: placing an option and following the singles leads to a square with no options or a
+    // value with nowhere to go, so the option is removed
+    fn find_nishio(&self) -> Option<SolveStep> {
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_some() {
+                    continue;
+                }
+                for &option in &self.grid[r][c].options {
+                    if self.assume(r, c, option).is_none() {
+                        let assumption: String = format!("{} at {}", option, square_name(r, c));
+                        return Some(forcing_step("Nishio", &assumption, &[(r, c)], None, vec![(r, c, option)]));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Cell Forcing Chain: every option of a square leads to the same placement or elimination,
+    // so it holds whichever option is true
+    fn find_cell_forcing(&self) -> Option<SolveStep> {
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_some() || self.grid[r][c].options.len() < 2 {
+                    continue;
+                }
+                let branches: Vec<(usize, usize, u32)> =
+                    self.grid[r][c].options.iter().map(|&option| (r, c, option)).collect();
+                let assumption: String = format!("every option of {}", square_name(r, c));
+                if let Some(step) = self.forcing_conclusion("Cell Forcing Chain", assumption, &branches) {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+
+    // Region Forcing Chain: every place for a value in a unit leads to the same placement or
+    // elimination, so it holds wherever the value goes
+    fn find_region_forcing(&self) -> Option<SolveStep> {
+        for unit in 0..27 {
+            for value in 1..=9u32 {
+                let spots: Vec<(usize, usize)> = self.option_squares(&unit_squares(unit), value);
+                if spots.len() < 2 {
+                    continue;
+                }
+                let branches: Vec<(usize, usize, u32)> = spots.iter().map(|&(r, c)| (r, c, value)).collect();
+                let assumption: String = format!("every place for {} in {}", value, unit_name(unit));
+                if let Some(step) = self.forcing_conclusion("Region Forcing Chain", assumption, &branches) {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+
+    // Follow each branch and find a placement, or else eliminations, which all of them agree on.
+    // One branch must be true, so a branch leading to a contradiction is left out
+    fn forcing_conclusion(&self, name: &str, assumption: String, branches: &[(usize, usize, u32)]) -> Option<SolveStep> {
+        let results: Vec<Sudoku> = branches.iter().filter_map(|&(r, c, value)| self.assume(r, c, value)).collect();
+        let squares: Vec<(usize, usize)> = branches.iter().map(|&(r, c, _)| (r, c)).collect();
+        if results.is_empty() {
+            return None;
+        }
+
+        let mut eliminations: Vec<(usize, usize, u32)> = vec![];
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_some() {
+                    continue;
+                }
+                if let Some(value) = results[0].grid[r][c].value {
+                    if results.iter().all(|result| result.grid[r][c].value == Some(value)) {
+                        return Some(forcing_step(name, &assumption, &squares, Some((r, c, value)), vec![]));
+                    }
+                }
+                for &option in &self.grid[r][c].options {
+                    let removed = |result: &Sudoku| {
+                        result.grid[r][c].value != Some(option) && !result.grid[r][c].options.contains(&option)
+                    };
+                    if results.iter().all(removed) {
+                        eliminations.push((r, c, option));
+                    }
+                }
+            }
+        }
+
+        if eliminations.is_empty() {
+            return None;
+        }
+        Some(forcing_step(name, &assumption, &squares, None, eliminations))
+    }
+
+    // Place a value on a copy of the grid and follow the singles it leads to
+    // Returns None if this leads to a contradiction
+    fn assume(&self, r: usize, c: usize, value: u32) -> Option<Sudoku> {
+        let mut branch: Sudoku = self.clone();
+        branch.place_value(r, c, value);
+        if branch.propagate_singles() {
+            Some(branch)
+        } else {
+            None
+        }
+    }
+
+    // Place a value and remove it from the options of the squares it sees, keeping all other eliminations
+    fn place_value(&mut self, r: usize, c: usize, value: u32) {
+        self.grid[r][c].value = Some(value);
+        self.grid[r][c].options = Vec::default();
+        self.remove_peer_options(r, c, value);
+    }
+
+    // Keep placing naked and hidden singles from the current options until nothing changes
+    // Returns false if a square is left with no options or a value has nowhere to go in a unit
+    fn propagate_singles(&mut self) -> bool {
+        let mut changed: bool = true;
+        while changed {
+            changed = false;
+            for r in 0..9 {
+                for c in 0..9 {
+                    if self.grid[r][c].value.is_some() {
+                        continue;
+                    }
+                    match self.grid[r][c].options.len() {
+                        0 => return false,
+                        1 => {
+                            self.place_value(r, c, self.grid[r][c].options[0]);
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            for unit in 0..27 {
+                let squares: Vec<(usize, usize)> = unit_squares(unit);
+                for value in 1..=9u32 {
+                    if squares.iter().any(|&(r, c)| self.grid[r][c].value == Some(value)) {
+                        continue;
+                    }
+                    match self.option_squares(&squares, value)[..] {
+                        [] => return false,
+                        [(r, c)] => {
+                            self.place_value(r, c, value);
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+// Build the step for what the assumption leads to, e.g. "Cell Forcing Chain: every option of R1C1 leads to 5 at R4C4".
+// The squares are those of the values which were assumed
+fn forcing_step(
+    name: &str,
+    assumption: &str,
+    squares: &[(usize, usize)],
+    placement: Option<(usize, usize, u32)>,
+    eliminations: Vec<(usize, usize, u32)>,
+) -> SolveStep {
+    let result: String = match placement {
+        Some((r, c, value)) => format!("leads to {} at {}", value, square_name(r, c)),
+        None => {
+            let removed: Vec<String> =
+                eliminations.iter().map(|&(r, c, value)| format!("{}{}", value, square_name(r, c))).collect();
+            format!("removes {}", removed.join(", "))
+        }
+    };
+    let description: String = format!("{}: {} {}", name, assumption, result);
+    let difficulty: f32 = match name {
+        "Nishio" => 7.6,
+        "Cell Forcing Chain" => 8.2,
+        _ => 8.3,
+    };
+    SolveStep { placement, eliminations, description, ..SolveStep::default() }.involving(squares).rated(difficulty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_a_nishio() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_forcing(), "Nishio", None, &[(3, 5, 1)]);
+    }
+
+    #[test]
+    fn finds_a_cell_forcing_chain() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3   | 6  4  7
+             8    1   6 | 4   9  7   | 3  2  5
+             4    7   3 | 5   6  2   | 1  9  8
+             3    4   7 | 8   2  16  | 9  5  16
+             169  8   2 | 69  4  5   | 7  3  16
+             169  69  5 | 3   7  169 | 4  8  2
+             7    3   4 | 2   1  8   | 5  6  9
+             69   5   8 | 7   3  69  | 2  1  4
+             2    69  1 | 69  5  4   | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_cell_forcing(), "Cell Forcing Chain", None, &[(5, 0, 6)]);
+    }
+
+    #[test]
+    fn finds_a_region_forcing_chain() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "28   6    78  | 2578   1235   9   | 4     1235   1237
+             5    148  3   | 278    24     17  | 167   126    9
+             29   19   479 | 257    12345  6   | 1357  1235   8
+             369  479  459 | 25679  125    137 | 1378  1368   1367
+             368  78   58  | 56     15     4   | 2     9      1367
+             369  2    1   | 679    8      37  | 367   4      5
+             1    5    68  | 3      7      2   | 9     68     4
+             4    89   289 | 1      6      58  | 358   7      23
+             7    3    268 | 4      9      58  | 1568  12568  126",
+        )
+        .unwrap();
+        assert_step(sudoku.find_region_forcing(), "Region Forcing Chain", None, &[(0, 4, 1), (1, 6, 1), (1, 7, 1), (2, 6, 1), (2, 7, 1)]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{sqr, Difficulty, Sudoku, SudokuSquare};
+
+// How many complete grids to try before giving up on reaching the difficulty
+const MAX_ATTEMPTS: u32 = 200;
+
+// A small xorshift random number generator, so a seed always gives the same puzzle
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // Xorshift gets stuck on zero, and nearby seeds should still give different puzzles
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // Pick a number from 0 up to but not including n
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    // Put the items in a random order
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j: usize = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+// Make a seed from the current time, for when the puzzle doesn't need to be repeatable
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+// A pattern which the clues of a generated puzzle must follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    // The clues look the same after turning the grid half way around
+    Rotational180,
+    // The clues look the same after turning the grid a quarter of the way around
+    Rotational90,
+    // The clues are mirrored across the diagonal from the top left to the bottom right
+    Diagonal,
+    // The clues are mirrored across the middle column
+    Mirror,
+}
+
+impl Symmetry {
+    // Every symmetry, to choose from
+    pub const ALL: [Symmetry; 5] =
+        [Symmetry::None, Symmetry::Rotational180, Symmetry::Rotational90, Symmetry::Diagonal, Symmetry::Mirror];
+
+    // List the squares which must be clues together with the given one, including itself
+    fn group(&self, r: usize, c: usize) -> Vec<(usize, usize)> {
+        let mut group: Vec<(usize, usize)> = match self {
+            Symmetry::None => vec![(r, c)],
+            Symmetry::Rotational180 => vec![(r, c), (8 - r, 8 - c)],
+            Symmetry::Rotational90 => vec![(r, c), (c, 8 - r), (8 - r, 8 - c), (8 - c, r)],
+            Symmetry::Diagonal => vec![(r, c), (c, r)],
+            Symmetry::Mirror => vec![(r, c), (r, 8 - c)],
+        };
+        group.sort();
+        group.dedup();
+        group
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name: &str = match self {
+            Symmetry::None => "No symmetry",
+            Symmetry::Rotational180 => "Rotational 180°",
+            Symmetry::Rotational90 => "Rotational 90°",
+            Symmetry::Diagonal => "Diagonal",
+            Symmetry::Mirror => "Mirror",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Options for generating a new puzzle
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    // The band which the puzzle's rating must fall in
+    pub difficulty: Difficulty,
+    pub symmetry: Symmetry,
+    // Only accept puzzles where every clue is needed for a unique solution
+    pub minimal: bool,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        GeneratorSettings { difficulty: Difficulty::Medium, symmetry: Symmetry::None, minimal: false }
+    }
+}
+
+impl Sudoku {
+    // Generate a puzzle with a unique solution whose rating falls in the difficulty band, with
+    // clues following the symmetry, and with no clue to spare if it must be minimal
+    // Returns None if no such puzzle was found within the attempts allowed
+    pub fn generate(seed: u64, settings: &GeneratorSettings) -> Option<Sudoku> {
+        let mut rng: Rng = Rng::new(seed);
+        for _ in 0..MAX_ATTEMPTS {
+            let puzzle: Sudoku = Sudoku::remove_clues(random_solution(&mut rng), settings, &mut rng);
+            if settings.minimal && !puzzle.is_minimal() {
+                continue;
+            }
+            if puzzle.rate().is_some_and(|rating| rating.difficulty == settings.difficulty) {
+                return Some(puzzle);
+            }
+        }
+        None
+    }
+
+    // Check whether every clue is needed, so removing any one of them leaves more than one solution
+    pub fn is_minimal(&self) -> bool {
+        let mut puzzle: Sudoku = self.puzzle();
+        for r in 0..9 {
+            for c in 0..9 {
+                let square: SudokuSquare = puzzle.grid[r][c].clone();
+                if square.value.is_none() {
+                    continue;
+                }
+                puzzle.grid[r][c] = SudokuSquare::default();
+                let unique: bool = puzzle.count_solutions(2) == 1;
+                puzzle.grid[r][c] = square;
+                if unique {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // Take clues away from a complete grid in a random order, a symmetric group at a time, keeping
+    // each group which is needed for a unique solution. Unless the puzzle must be minimal, groups
+    // are also kept to stop the puzzle getting harder than the difficulty band
+    fn remove_clues(solution: Vec<Vec<u32>>, settings: &GeneratorSettings, rng: &mut Rng) -> Sudoku {
+        let mut puzzle: Sudoku =
+            Sudoku::new(solution.iter().map(|row| row.iter().map(|&value| sqr(value)).collect()).collect());
+        let mut squares: Vec<(usize, usize)> = (0..81).map(|i| (i / 9, i % 9)).collect();
+        rng.shuffle(&mut squares);
+
+        for (r, c) in squares {
+            if puzzle.grid[r][c].value.is_none() {
+                // Already taken away along with another square of its group
+                continue;
+            }
+            let group: Vec<(usize, usize)> = settings.symmetry.group(r, c);
+            for &(i, j) in &group {
+                puzzle.grid[i][j] = SudokuSquare::default();
+            }
+
+            // Rating also checks the puzzle still has a unique solution, but nothing is too hard to be
+            // Extreme, so then only the solutions need counting
+            let keep: bool = if settings.minimal || settings.difficulty == Difficulty::Extreme {
+                puzzle.count_solutions(2) != 1
+            } else {
+                match puzzle.rate() {
+                    Some(rating) => rating.difficulty > settings.difficulty,
+                    None => true,
+                }
+            };
+            if keep {
+                for &(i, j) in &group {
+                    puzzle.grid[i][j] = sqr(solution[i][j]);
+                }
+            }
+        }
+        puzzle.grid_edited();
+        puzzle
+    }
+}
+
+// Fill an empty grid with random values which follow the rules
+fn random_solution(rng: &mut Rng) -> Vec<Vec<u32>> {
+    let mut grid: Vec<Vec<u32>> = vec![vec![0; 9]; 9];
+    fill_square(&mut grid, 0, rng);
+    grid
+}
+
+// Try each value in a random order in the square, then fill the rest of the grid after it
+// Returns false if no value leads to a full grid
+fn fill_square(grid: &mut [Vec<u32>], i: usize, rng: &mut Rng) -> bool {
+    if i == 81 {
+        return true;
+    }
+    let (r, c) = (i / 9, i % 9);
+    let mut values: Vec<u32> = (1..=9).collect();
+    rng.shuffle(&mut values);
+
+    for value in values {
+        let (r_start, c_start) = (r - r % 3, c - c % 3);
+        let allowed: bool = (0..9).all(|j| {
+            grid[r][j] != value && grid[j][c] != value && grid[r_start + j / 3][c_start + j % 3] != value
+        });
+        if allowed {
+            grid[r][c] = value;
+            if fill_square(grid, i + 1, rng) {
+                return true;
+            }
+            grid[r][c] = 0;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Check that taking away any one clue leaves more than one solution
+    fn clues_needed(puzzle: &Sudoku) -> bool {
+        (0..81).map(|i| (i / 9, i % 9)).filter(|&(r, c)| puzzle.grid[r][c].value.is_some()).all(|(r, c)| {
+            let mut fewer: Sudoku = puzzle.clone();
+            fewer.grid[r][c] = SudokuSquare::default();
+            fewer.count_solutions(2) > 1
+        })
+    }
+
+    #[test]
+    fn minimal_puzzles_follow_each_symmetry() {
+        // Minimal puzzles are rare with the bigger symmetric groups, so each symmetry has a seed which finds one quickly
+        let seeds: [(Symmetry, u64); 5] = [
+            (Symmetry::None, 18),
+            (Symmetry::Rotational180, 15),
+            (Symmetry::Rotational90, 6),
+            (Symmetry::Diagonal, 5),
+            (Symmetry::Mirror, 6),
+        ];
+        for (symmetry, seed) in seeds {
+            let settings = GeneratorSettings { difficulty: Difficulty::Hard, symmetry, minimal: true };
+            let puzzle: Sudoku = Sudoku::generate(seed, &settings).unwrap_or_else(|| panic!("no {} puzzle", symmetry));
+            for (r, c) in (0..81).map(|i| (i / 9, i % 9)) {
+                let clue: bool = puzzle.grid[r][c].value.is_some();
+                assert!(symmetry.group(r, c).iter().all(|&(i, j)| puzzle.grid[i][j].value.is_some() == clue));
+                assert_eq!(puzzle.grid[r][c].given, clue);
+            }
+            assert!(clues_needed(&puzzle), "{} puzzle has a clue to spare", symmetry);
+            assert_eq!(puzzle.rate().map(|rating| rating.difficulty), Some(Difficulty::Hard));
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_puzzle() {
+        let settings = GeneratorSettings { difficulty: Difficulty::Medium, symmetry: Symmetry::Rotational180, minimal: false };
+        let puzzle: Sudoku = Sudoku::generate(42, &settings).unwrap();
+        assert_eq!(Sudoku::generate(42, &settings).unwrap().grid, puzzle.grid);
+    }
+}
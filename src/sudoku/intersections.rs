@@ -0,0 +1,240 @@
+use super::{square_name, squares_to_string, unit_name, unit_squares, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Pointing: when a value's only options in a 3x3 box all lie in one row or column,
+    // the value must go in that box and can be removed from the rest of the row or column
+    pub fn find_pointing(&self) -> Option<SolveStep> {
+        for b in 0..9usize {
+            let box_squares: Vec<(usize, usize)> = unit_squares(18 + b);
+            for value in 1..=9u32 {
+                let spots: Vec<(usize, usize)> = self.option_squares(&box_squares, value);
+                if spots.len() < 2 {
+                    continue;
+                }
+
+                // Check whether the spots share a row or column
+                let (r, c) = spots[0];
+                let line: usize = if spots.iter().all(|&(i, _)| i == r) {
+                    r
+                } else if spots.iter().all(|&(_, j)| j == c) {
+                    9 + c
+                } else {
+                    continue;
+                };
+
+                if let Some(step) = self.remove_outside(line, &box_squares, value, &spots, "Pointing", 18 + b) {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+
+    // Claiming (box/line reduction): when a value's only options in a row or column all lie in
+    // one 3x3 box, the value must go in that line and can be removed from the rest of the box
+    pub fn find_claiming(&self) -> Option<SolveStep> {
+        for line in 0..18usize {
+            let line_squares: Vec<(usize, usize)> = unit_squares(line);
+            for value in 1..=9u32 {
+                let spots: Vec<(usize, usize)> = self.option_squares(&line_squares, value);
+                if spots.len() < 2 {
+                    continue;
+                }
+
+                // Check whether the spots share a 3x3 box
+                let (r, c) = spots[0];
+                let r_start = r - r % 3;
+                let c_start = c - c % 3;
+                if !spots.iter().all(|&(i, j)| i - i % 3 == r_start && j - j % 3 == c_start) {
+                    continue;
+                }
+
+                let b: usize = r_start + c_start / 3;
+                if let Some(step) = self.remove_outside(18 + b, &line_squares, value, &spots, "Claiming", line) {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+
+    // Sue de Coq: two or three squares where a box meets a row or column, with at least two more
+    // options than squares. Together with some squares from the rest of the line and some from the
+    // rest of the box, which share no options with each other, they make as many squares as
+    // options, so every option is placed exactly once. The line squares' options are removed from
+    // the rest of the line, the box squares' options from the rest of the box, and options found
+    // only in the intersection from both
+    pub fn find_sue_de_coq(&self) -> Option<SolveStep> {
+        for b in 0..9usize {
+            let box_squares: Vec<(usize, usize)> = unit_squares(18 + b);
+            let r_start = (b / 3) * 3;
+            let c_start = (b % 3) * 3;
+            let lines: Vec<usize> = (r_start..r_start + 3).chain((9 + c_start)..(9 + c_start + 3)).collect();
+
+            for line in lines {
+                let line_squares: Vec<(usize, usize)> = unit_squares(line);
+                let empty = |square: &&(usize, usize)| self.grid[square.0][square.1].value.is_none();
+                let intersection: Vec<(usize, usize)> =
+                    line_squares.iter().filter(|sq| box_squares.contains(sq)).filter(empty).copied().collect();
+                let line_rest: Vec<(usize, usize)> =
+                    line_squares.iter().filter(|sq| !box_squares.contains(sq)).filter(empty).copied().collect();
+                let box_rest: Vec<(usize, usize)> =
+                    box_squares.iter().filter(|sq| !line_squares.contains(sq)).filter(empty).copied().collect();
+                let line_sets: Vec<(Vec<(usize, usize)>, u16)> = self.square_subsets(&line_rest);
+                let box_sets: Vec<(Vec<(usize, usize)>, u16)> = self.square_subsets(&box_rest);
+
+                for (core, core_mask) in self.square_subsets(&intersection) {
+                    if core.len() < 2 || (core_mask.count_ones() as usize) < core.len() + 2 {
+                        continue;
+                    }
+                    for (line_set, line_mask) in &line_sets {
+                        for (box_set, box_mask) in &box_sets {
+                            let all_mask: u16 = core_mask | line_mask | box_mask;
+                            if line_mask & box_mask != 0
+                                || all_mask.count_ones() as usize != core.len() + line_set.len() + box_set.len()
+                            {
+                                continue;
+                            }
+
+                            let line_values: u16 = line_mask | (core_mask & !box_mask);
+                            let box_values: u16 = box_mask | (core_mask & !line_mask);
+                            let mut eliminations: Vec<(usize, usize, u32)> = vec![];
+                            for (rest, set, values) in [(&line_rest, line_set, line_values), (&box_rest, box_set, box_values)] {
+                                // The intersection squares outside of the core are in both the line and the box
+                                let targets = rest.iter().chain(intersection.iter());
+                                for &(r, c) in targets.filter(|sq| !set.contains(sq) && !core.contains(sq)) {
+                                    for &value in &self.grid[r][c].options {
+                                        if values & (1 << value) != 0 && !eliminations.contains(&(r, c, value)) {
+                                            eliminations.push((r, c, value));
+                                        }
+                                    }
+                                }
+                            }
+
+                            if !eliminations.is_empty() {
+                                let removed: Vec<String> = eliminations
+                                    .iter()
+                                    .map(|&(r, c, value)| format!("{}{}", value, square_name(r, c)))
+                                    .collect();
+                                let description: String = format!(
+                                    "Sue de Coq: {} with {} in {} and {} in {}, removed {}",
+                                    squares_to_string(&core),
+                                    squares_to_string(line_set),
+                                    unit_name(line),
+                                    squares_to_string(box_set),
+                                    unit_name(18 + b),
+                                    removed.join(", ")
+                                );
+                                let squares: Vec<(usize, usize)> = [&core[..], &line_set[..], &box_set[..]].concat();
+                                return Some(SolveStep { eliminations, description, ..SolveStep::default() }.involving(&squares));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // List every non-empty subset of the squares, along with a bitmask of their combined options
+    fn square_subsets(&self, squares: &[(usize, usize)]) -> Vec<(Vec<(usize, usize)>, u16)> {
+        (1..(1u32 << squares.len()))
+            .map(|subset| {
+                let chosen: Vec<(usize, usize)> =
+                    (0..squares.len()).filter(|i| subset & (1 << i) != 0).map(|i| squares[i]).collect();
+                let mask: u16 = chosen
+                    .iter()
+                    .flat_map(|&(r, c)| self.grid[r][c].options.iter())
+                    .fold(0, |mask, &value| mask | (1 << value));
+                (chosen, mask)
+            })
+            .collect()
+    }
+
+    // Remove the value from every square of the target unit which is not also in the source unit
+    fn remove_outside(
+        &self,
+        target: usize,
+        source_squares: &[(usize, usize)],
+        value: u32,
+        spots: &[(usize, usize)],
+        technique: &str,
+        source: usize,
+    ) -> Option<SolveStep> {
+        let removed: Vec<(usize, usize)> = unit_squares(target)
+            .into_iter()
+            .filter(|&(r, c)| !source_squares.contains(&(r, c)) && self.grid[r][c].options.contains(&value))
+            .collect();
+
+        if removed.is_empty() {
+            return None;
+        }
+        let description: String = format!(
+            "{}: {} in {} locked to {} at {}, removed from {}",
+            technique,
+            value,
+            unit_name(source),
+            unit_name(target),
+            squares_to_string(spots),
+            squares_to_string(&removed)
+        );
+        Some(SolveStep::remove(value, &removed, description).involving(spots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_a_sue_de_coq() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "6  3  1 | 8  29  5  | 4   279  79
+             5  8  7 | 6  4   29 | 1   39   239
+             4  2  9 | 7  3   1  | 8   6    5
+             2  7  5 | 9  1   3  | 6   8    4
+             8  9  4 | 5  7   6  | 23  1    23
+             3  1  6 | 2  8   4  | 79  5    79
+             9  4  2 | 1  5   8  | 37  37   6
+             7  5  8 | 3  6   29 | 29  4    1
+             1  6  3 | 4  29  7  | 5   29   8",
+        )
+        .unwrap();
+        assert_step(sudoku.find_sue_de_coq(), "Sue de Coq", None, &[(1, 8, 9)]);
+    }
+
+    #[test]
+    fn finds_pointing() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3   | 6  4  7
+             8    1   6 | 4   9  7   | 3  2  5
+             4    7   3 | 5   6  2   | 1  9  8
+             3    4   7 | 8   2  16  | 9  5  16
+             19   8   2 | 69  4  5   | 7  3  16
+             169  69  5 | 3   7  169 | 4  8  2
+             7    3   4 | 2   1  8   | 5  6  9
+             69   5   8 | 7   3  69  | 2  1  4
+             2    69  1 | 69  5  4   | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_pointing(), "Pointing", None, &[(5, 5, 6)]);
+    }
+
+    #[test]
+    fn finds_claiming() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "46   1  46 | 8   5    7 | 24  3   9
+             469  8  3  | 26  26   1 | 5   24  7
+             7    5  2  | 3   9    4 | 6   1   8
+             2    4  7  | 9   3    6 | 1   8   5
+             5    3  1  | 4   7    8 | 9   6   2
+             8    6  9  | 5   1    2 | 3   7   4
+             3    7  46 | 26  268  9 | 24  5   1
+             46   9  8  | 1   26   5 | 7   24  3
+             1    2  5  | 7   4    3 | 8   9   6",
+        )
+        .unwrap();
+        assert_step(sudoku.find_claiming(), "Claiming", None, &[(1, 0, 6)]);
+    }
+}
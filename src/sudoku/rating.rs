@@ -0,0 +1,144 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::{technique, SolveStep, Sudoku, Technique};
+
+// The difficulty given to a puzzle which the techniques can't finish, so it needs guessing
+const BACKTRACKING_DIFFICULTY: f32 = 10.0;
+
+// A difficulty band for a puzzle, ordered from easiest to hardest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Extreme,
+}
+
+impl Difficulty {
+    // Every band, from easiest to hardest
+    pub const ALL: [Difficulty; 5] =
+        [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert, Difficulty::Extreme];
+
+    // Find the band for a score: hidden singles are Easy, naked singles Medium, up to subsets and
+    // wings Hard, up to chains Expert, and forcing chains or guessing Extreme
+    pub fn from_score(score: f32) -> Difficulty {
+        if score <= 1.5 {
+            Difficulty::Easy
+        } else if score <= 2.5 {
+            Difficulty::Medium
+        } else if score <= 4.5 {
+            Difficulty::Hard
+        } else if score <= 7.0 {
+            Difficulty::Expert
+        } else {
+            Difficulty::Extreme
+        }
+    }
+
+    // Name the band for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+            Difficulty::Extreme => "Extreme",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+// The rating of a puzzle, from the hardest step needed when always taking the easiest one available
+#[derive(Debug, Clone)]
+pub struct Rating {
+    // The difficulty of the hardest step, roughly on the Sudoku Explainer scale
+    pub score: f32,
+    pub difficulty: Difficulty,
+    // The technique of the hardest step, or "Backtracking" if the techniques get stuck
+    pub hardest: &'static str,
+    pub steps: Vec<SolveStep>,
+}
+
+impl Rating {
+    // Describe the rating for display, e.g. "Rated 4.2 (Hard) over 52 steps, hardest technique Wings"
+    pub fn description(&self) -> String {
+        format!(
+            "Rated {:.1} ({}) over {} steps, hardest technique {}",
+            self.score,
+            self.difficulty.label(),
+            self.steps.len(),
+            self.hardest
+        )
+    }
+}
+
+impl Sudoku {
+    // Rate the puzzle made from the given squares by solving it with the easiest step available each
+    // time, using every technique in the settings whether or not it is turned on
+    // Returns None if the puzzle doesn't have exactly one solution
+    pub fn rate(&self) -> Option<Rating> {
+        let mut puzzle: Sudoku = self.puzzle();
+        if puzzle.count_solutions(2) != 1 {
+            return None;
+        }
+        puzzle.unique.set(Some(true));
+        puzzle.update_all_options();
+
+        // Try the techniques from easiest to hardest, so the first step found is usually the easiest
+        let mut techniques: Vec<Arc<dyn Technique>> = self.settings.techniques.clone();
+        techniques.sort_by(|a, b| a.difficulty().total_cmp(&b.difficulty()));
+
+        let mut steps: Vec<SolveStep> = vec![];
+        while let Some(step) = puzzle.easiest_step(&techniques) {
+            puzzle.apply_step(&step);
+            steps.push(step);
+        }
+
+        let solved: bool = puzzle.grid.iter().flatten().all(|square| square.value.is_some());
+        let (score, hardest) = if solved {
+            steps.iter().fold((0.0, "None"), |(score, hardest), step| {
+                if step.difficulty > score {
+                    (step.difficulty, step.technique)
+                } else {
+                    (score, hardest)
+                }
+            })
+        } else {
+            (BACKTRACKING_DIFFICULTY, "Backtracking")
+        };
+        Some(Rating { score, difficulty: Difficulty::from_score(score), hardest, steps })
+    }
+
+    // Rate the puzzle and show the rating as the status
+    pub fn rate_puzzle(&mut self) {
+        self.status = match self.rate() {
+            Some(rating) => rating.description(),
+            None => "Only a puzzle with a unique solution can be rated".to_string(),
+        };
+    }
+
+    // Find the easiest step out of the techniques, which are sorted from easiest to hardest. A
+    // technique can find a step harder than its usual difficulty, so the search carries on until
+    // no later technique could find an easier one
+    fn easiest_step(&self, techniques: &[Arc<dyn Technique>]) -> Option<SolveStep> {
+        let mut easiest: Option<SolveStep> = None;
+        for technique in techniques {
+            if easiest.as_ref().is_some_and(|step| step.difficulty <= technique.difficulty()) {
+                break;
+            }
+            if let Some(step) = technique::find_step_with(technique.as_ref(), self) {
+                if easiest.as_ref().is_none_or(|easiest| step.difficulty < easiest.difficulty) {
+                    easiest = Some(step);
+                }
+            }
+        }
+        easiest
+    }
+}
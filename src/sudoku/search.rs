@@ -0,0 +1,234 @@
+use super::{unit_squares, Sudoku};
+
+impl Sudoku {
+    // Solve the grid by depth-first search, filling in the values if a solution is found
+    // Returns the number of guesses needed, or None if the grid has no solution
+    pub fn solve_backtrack(&mut self) -> Option<u32> {
+        let mut guesses: u32 = 0;
+        let mut search: Sudoku = self.clone();
+        if self.is_consistent() && search.backtrack(&mut guesses) {
+            // Copy the solved values back, keeping the given flags of the original grid
+            for r in 0..9 {
+                for c in 0..9 {
+                    if self.grid[r][c].value.is_none() {
+                        self.grid[r][c].value = search.grid[r][c].value;
+                        self.grid[r][c].options = Vec::default();
+                    }
+                }
+            }
+            Some(guesses)
+        } else {
+            None
+        }
+    }
+
+    // Count the solutions of the current grid, stopping once the limit is reached
+    // A limit of 2 is enough to tell apart no solution, a unique solution, and more than one
+    pub fn count_solutions(&self, limit: u32) -> u32 {
+        let mut count: u32 = 0;
+        if self.is_consistent() {
+            self.clone().count_branch(limit, &mut count);
+        }
+        count
+    }
+
+    // Make a copy of the puzzle with only the given squares filled in
+    pub fn puzzle(&self) -> Sudoku {
+        let mut puzzle: Sudoku = self.clone();
+        for r in 0..9 {
+            for c in 0..9 {
+                if !puzzle.grid[r][c].given {
+                    puzzle.grid[r][c].value = None;
+                }
+            }
+        }
+        puzzle.grid_edited();
+        puzzle
+    }
+
+    // Check that the puzzle made from only the given squares has exactly one solution
+    pub fn verify_puzzle(&mut self) {
+        self.status = match self.puzzle().count_solutions(2) {
+            0 => "Sudoku puzzle has no solution".to_string(),
+            1 => "Sudoku puzzle has a unique solution".to_string(),
+            _ => "Sudoku puzzle has more than one solution".to_string(),
+        };
+    }
+
+    // Recursive depth-first search, guessing on the most constrained square each step
+    fn backtrack(&mut self, guesses: &mut u32) -> bool {
+        if !self.propagate() {
+            return false;
+        }
+
+        // No empty squares left, so the grid is solved
+        let (r, c) = match self.most_constrained_square() {
+            Some((r, c)) => (r, c),
+            None => return true,
+        };
+
+        // Try each option in turn, keeping the first branch which leads to a solution
+        for option in self.grid[r][c].options.clone() {
+            *guesses += 1;
+            let mut branch: Sudoku = self.clone();
+            branch.grid[r][c].value = Some(option);
+            if branch.backtrack(guesses) {
+                *self = branch;
+                return true;
+            }
+        }
+        false
+    }
+
+    // Recursive depth-first search which visits every solution until the limit is reached
+    fn count_branch(&mut self, limit: u32, count: &mut u32) {
+        if !self.propagate() {
+            return;
+        }
+
+        // No empty squares left, so this branch is one solution
+        let (r, c) = match self.most_constrained_square() {
+            Some((r, c)) => (r, c),
+            None => {
+                *count += 1;
+                return;
+            }
+        };
+
+        for option in self.grid[r][c].options.clone() {
+            if *count >= limit {
+                return;
+            }
+            let mut branch: Sudoku = self.clone();
+            branch.grid[r][c].value = Some(option);
+            branch.count_branch(limit, count);
+        }
+    }
+
+    // Find the empty square with the fewest options to guess on
+    fn most_constrained_square(&self) -> Option<(usize, usize)> {
+        let mut guess_square: Option<(usize, usize)> = None;
+        let mut fewest_options: usize = 10;
+        for r in 0..9 {
+            for c in 0..9 {
+                let options_len = self.grid[r][c].options.len();
+                if self.grid[r][c].value.is_none() && options_len < fewest_options {
+                    guess_square = Some((r, c));
+                    fewest_options = options_len;
+                }
+            }
+        }
+        guess_square
+    }
+
+    // Check that no value is repeated within a row, column, or box
+    fn is_consistent(&self) -> bool {
+        self.find_duplicate().is_none()
+    }
+
+    // Update the options of every empty square and fill in any square with only one option, or any
+    // value with only one square left in a unit, repeating until nothing changes
+    // Returns false if a square is left with no options or a value has nowhere to go
+    fn propagate(&mut self) -> bool {
+        let mut changed: bool = true;
+        while changed {
+            changed = false;
+            for r in 0..9 {
+                for c in 0..9 {
+                    if self.grid[r][c].value.is_none() {
+                        self.update_options(r, c);
+                        match self.grid[r][c].options.len() {
+                            0 => return false,
+                            1 => {
+                                self.grid[r][c].value = Some(self.grid[r][c].options[0]);
+                                changed = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            if changed {
+                continue;
+            }
+
+            // Only look for hidden singles once every square's options are up to date
+            for unit in 0..27 {
+                let squares = unit_squares(unit);
+                for value in 1..=9u32 {
+                    if squares.iter().any(|&(r, c)| self.grid[r][c].value == Some(value)) {
+                        continue;
+                    }
+                    let mut spots = squares
+                        .iter()
+                        .filter(|&&(r, c)| self.grid[r][c].value.is_none() && self.grid[r][c].options.contains(&value));
+                    match (spots.next(), spots.next()) {
+                        (None, _) => return false,
+                        (Some(&(r, c)), None) => {
+                            self.grid[r][c].value = Some(value);
+                            self.grid[r][c].options = vec![value];
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+                if changed {
+                    break;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{empty_board, med_board_1};
+    use super::*;
+
+    #[test]
+    fn count_solutions_tells_none_one_and_many_apart() {
+        let mut repeated: Sudoku = Sudoku::new(med_board_1());
+        repeated.grid[0][0] = repeated.grid[0][1].clone();
+        assert_eq!(repeated.count_solutions(2), 0);
+        assert_eq!(Sudoku::new(med_board_1()).count_solutions(2), 1);
+        assert_eq!(Sudoku::new(empty_board()).count_solutions(2), 2);
+    }
+
+    #[test]
+    fn verify_puzzle_reports_the_solution_count() {
+        let mut repeated: Sudoku = Sudoku::new(med_board_1());
+        repeated.grid[0][0] = repeated.grid[0][1].clone();
+        let cases = [
+            (repeated, "Sudoku puzzle has no solution"),
+            (Sudoku::new(med_board_1()), "Sudoku puzzle has a unique solution"),
+            (Sudoku::new(empty_board()), "Sudoku puzzle has more than one solution"),
+        ];
+        for (mut sudoku, status) in cases {
+            sudoku.verify_puzzle();
+            assert_eq!(sudoku.status, status);
+        }
+    }
+
+    #[test]
+    fn solve_falls_back_on_backtracking_when_the_techniques_stall() {
+        let line: &str = "800000000003600000070090200050007000000045700000100030001000068008500010090000400";
+        let mut sudoku: Sudoku = Sudoku::from_line(line).unwrap();
+        sudoku.settings.set_singles_only(true);
+        let steps = sudoku.solve(false);
+
+        assert!(sudoku.solved, "{}", sudoku.status);
+        let guesses: &str = sudoku.status.strip_prefix("Sudoku puzzle solved with ").unwrap().trim_end_matches(" guesses");
+        assert!(guesses.parse::<u32>().unwrap() > 0);
+        // Every square the techniques left empty is its own backtracking step, and the givens are kept
+        let backtracked: usize = steps.iter().filter(|step| step.technique == "Backtracking").count();
+        let placed: usize = steps.iter().filter(|step| step.placement.is_some()).count();
+        assert!(backtracked > 0);
+        assert_eq!(placed, 81 - line.chars().filter(|&c| c != '0').count());
+        assert_eq!(sudoku.puzzle().to_line(), line.replace('0', "."));
+
+        let mut repeated: Sudoku = Sudoku::new(med_board_1());
+        repeated.grid[0][0] = repeated.grid[0][1].clone();
+        assert_eq!(repeated.solve_backtrack(), None);
+    }
+}
@@ -0,0 +1,226 @@
+use super::{sees, square_name, squares_to_string, unit_squares, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Find the first Skyscraper, 2-String Kite, or Empty Rectangle which removes at least one option
+    pub fn find_single_digit_pattern(&self) -> Option<SolveStep> {
+        for value in 1..=9u32 {
+            let pattern = self
+                .find_turbot(value, "Skyscraper", 0..9, 0..9)
+                .or_else(|| self.find_turbot(value, "Skyscraper", 9..18, 9..18))
+                .or_else(|| self.find_turbot(value, "2-String Kite", 0..9, 9..18))
+                .or_else(|| self.find_empty_rectangle(value));
+            if pattern.is_some() {
+                return pattern;
+            }
+        }
+        None
+    }
+
+    // Skyscraper and 2-String Kite: two strong links with one end of each seeing the other.
+    // If the first far end is not the value, then its near end is, so the second near end is
+    // not, and the second far end is. One of the far ends must be the value, so it is removed
+    // from squares seeing both. Two rows or two columns make a Skyscraper, while a row and a
+    // column (whose near ends then share a box) make a 2-String Kite
+    fn find_turbot(
+        &self,
+        value: u32,
+        name: &'static str,
+        first_units: std::ops::Range<usize>,
+        second_units: std::ops::Range<usize>,
+    ) -> Option<SolveStep> {
+        let first_links: Vec<[(usize, usize); 2]> =
+            first_units.filter_map(|unit| self.strong_link(unit, value)).collect();
+        let second_links: Vec<[(usize, usize); 2]> =
+            second_units.filter_map(|unit| self.strong_link(unit, value)).collect();
+
+        for (i, &first) in first_links.iter().enumerate() {
+            for (j, &second) in second_links.iter().enumerate() {
+                // Only look at each pair of links once when they come from the same kind of unit
+                if name == "Skyscraper" && j <= i {
+                    continue;
+                }
+
+                for (near1, far1) in [(first[0], first[1]), (first[1], first[0])] {
+                    for (near2, far2) in [(second[0], second[1]), (second[1], second[0])] {
+                        let squares = [near1, far1, near2, far2];
+                        let distinct: bool = (0..4).all(|a| (a + 1..4).all(|b| squares[a] != squares[b]));
+                        if !distinct || !sees(near1, near2) {
+                            continue;
+                        }
+
+                        // Far ends in the same line as each other would make this an X-Wing
+                        if name == "Skyscraper" && (far1.0 == far2.0 || far1.1 == far2.1) {
+                            continue;
+                        }
+
+                        let eliminations = self.squares_seeing_all(&[far1, far2], value);
+                        if !eliminations.is_empty() {
+                            return Some(pattern_step(name, value, &[[near1, far1], [near2, far2]], None, &eliminations));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Empty Rectangle: a box whose options for the value all lie along one row and one column of
+    // the box, with a strong link outside the box which has one end in that row. If the other end
+    // of the link is not the value then the first end clears the row from the box, so the box's
+    // column must hold the value. The value is removed where the box's column meets the row of
+    // the other end (and the same with rows and columns swapped)
+    fn find_empty_rectangle(&self, value: u32) -> Option<SolveStep> {
+        for b in 0..9usize {
+            let spots: Vec<(usize, usize)> = self.option_squares(&unit_squares(18 + b), value);
+            if spots.len() < 2 {
+                continue;
+            }
+
+            let r_start = (b / 3) * 3;
+            let c_start = (b % 3) * 3;
+            for box_r in r_start..r_start + 3 {
+                for box_c in c_start..c_start + 3 {
+                    if !spots.iter().all(|&(r, c)| r == box_r || c == box_c) {
+                        continue;
+                    }
+
+                    // A strong link in a column outside the box, with one end in the box's row
+                    for link_c in (0..9usize).filter(|&c| c / 3 != b % 3) {
+                        if let Some(pattern) = self.empty_rectangle_link(value, b, 9 + link_c, (box_r, link_c), box_c, true) {
+                            return Some(pattern);
+                        }
+                    }
+                    // A strong link in a row outside the box, with one end in the box's column
+                    for link_r in (0..9usize).filter(|&r| r / 3 != b / 3) {
+                        if let Some(pattern) = self.empty_rectangle_link(value, b, link_r, (link_r, box_c), box_r, false) {
+                            return Some(pattern);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Check a strong link in the unit whose first end must be the given square, eliminating from
+    // the box's column (or row) where it meets the line of the link's other end
+    fn empty_rectangle_link(
+        &self,
+        value: u32,
+        b: usize,
+        unit: usize,
+        near: (usize, usize),
+        box_line: usize,
+        column_link: bool,
+    ) -> Option<SolveStep> {
+        let link: [(usize, usize); 2] = self.strong_link(unit, value)?;
+        let far: (usize, usize) = if link[0] == near {
+            link[1]
+        } else if link[1] == near {
+            link[0]
+        } else {
+            return None;
+        };
+
+        // The other end must be outside the box's band (or stack) for the target to be outside the box
+        let (r, c) = if column_link {
+            if far.0 / 3 == b / 3 {
+                return None;
+            }
+            (far.0, box_line)
+        } else {
+            if far.1 / 3 == b % 3 {
+                return None;
+            }
+            (box_line, far.1)
+        };
+
+        if self.grid[r][c].value.is_none() && self.grid[r][c].options.contains(&value) {
+            return Some(pattern_step("Empty Rectangle", value, &[[near, far]], Some(b), &[(r, c)]));
+        }
+        None
+    }
+}
+
+// Build the step for a pattern, e.g. "Skyscraper: 4 with strong links R1C2-R1C7, R5C2-R5C8, removed from R4C8"
+// An Empty Rectangle also names the box holding its options
+fn pattern_step(
+    name: &str,
+    value: u32,
+    links: &[[(usize, usize); 2]],
+    box_index: Option<usize>,
+    eliminations: &[(usize, usize)],
+) -> SolveStep {
+    let link_names: Vec<String> =
+        links.iter().map(|[a, b]| format!("{}-{}", square_name(a.0, a.1), square_name(b.0, b.1))).collect();
+    let in_box: String = match box_index {
+        Some(b) => format!(" in box {}", b + 1),
+        None => String::default(),
+    };
+    let description: String = format!(
+        "{}: {}{} with strong links {}, removed from {}",
+        name,
+        value,
+        in_box,
+        link_names.join(", "),
+        squares_to_string(eliminations)
+    );
+    SolveStep::remove(value, eliminations, description).involving(&links.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_a_skyscraper() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_single_digit_pattern(), "Skyscraper", None, &[(5, 5, 9), (8, 3, 9)]);
+    }
+
+    #[test]
+    fn finds_a_two_string_kite() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5   4   9  | 2  1  3 | 6    7   8
+             6   13  8  | 5  9  7 | 134  12  124
+             17  2   37 | 6  8  4 | 13   9   5
+             3   7   5  | 8  2  9 | 14   6   14
+             4   8   1  | 7  6  5 | 2    3   9
+             9   6   2  | 4  3  1 | 8    5   7
+             8   13  37 | 9  4  6 | 5    12  12
+             17  9   4  | 3  5  2 | 17   8   6
+             2   5   6  | 1  7  8 | 9    4   3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_single_digit_pattern(), "2-String Kite", None, &[(1, 6, 1)]);
+    }
+
+    #[test]
+    fn finds_an_empty_rectangle() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "1   2   4  | 7    8    9 | 6   3   5
+             6   7   39 | 34   2    5 | 49  1   8
+             5   39  8  | 46   36   1 | 49  2   7
+             2   8   6  | 9    1    4 | 57  57  3
+             39  39  7  | 8    5    6 | 2   4   1
+             4   1   5  | 2    7    3 | 8   6   9
+             8   6   39 | 35   4    7 | 1   59  2
+             39  5   1  | 36   369  2 | 37  8   4
+             7   4   2  | 135  39   8 | 35  59  6",
+        )
+        .unwrap();
+        assert_step(sudoku.find_single_digit_pattern(), "Empty Rectangle", None, &[(7, 3, 3)]);
+    }
+}
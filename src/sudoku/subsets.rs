@@ -0,0 +1,217 @@
+use super::{combinations, square_name, squares_to_string, unit_name, unit_squares, vec_to_string, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Naked subsets: when N squares in a unit share only N options between them, those options
+    // must go in those squares and can be removed from every other square in the unit
+    pub fn find_naked_subset(&self) -> Option<SolveStep> {
+        for size in 2..=4usize {
+            for unit in 0..27 {
+                // Only squares with between 2 and N options can be part of a naked subset
+                let squares: Vec<(usize, usize)> = unit_squares(unit)
+                    .into_iter()
+                    .filter(|&(r, c)| {
+                        let options_len = self.grid[r][c].options.len();
+                        self.grid[r][c].value.is_none() && options_len >= 2 && options_len <= size
+                    })
+                    .collect();
+
+                for subset in combinations(&squares, size) {
+                    let values: Vec<u32> = self.combined_options(&subset);
+                    if values.len() != size {
+                        continue;
+                    }
+
+                    // Remove the subset values from the rest of the unit
+                    let mut eliminations: Vec<(usize, usize, u32)> = vec![];
+                    for (r, c) in unit_squares(unit) {
+                        if self.grid[r][c].value.is_some() || subset.contains(&(r, c)) {
+                            continue;
+                        }
+                        for &value in values.iter().filter(|value| self.grid[r][c].options.contains(value)) {
+                            eliminations.push((r, c, value));
+                        }
+                    }
+
+                    if !eliminations.is_empty() {
+                        let description: String = format!(
+                            "Naked {}: {} at {} in {}, removed {}",
+                            subset_name(size),
+                            vec_to_string(&values),
+                            squares_to_string(&subset),
+                            unit_name(unit),
+                            removed_to_string(&eliminations)
+                        );
+                        let step = SolveStep { eliminations, description, ..SolveStep::default() };
+                        return Some(step.involving(&subset).rated(subset_difficulty(size, false)));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Hidden subsets: when N values can only go in the same N squares of a unit, those squares
+    // must hold those values and every other option can be removed from them
+    pub fn find_hidden_subset(&self) -> Option<SolveStep> {
+        for size in 2..=4usize {
+            for unit in 0..27 {
+                let squares: Vec<(usize, usize)> = unit_squares(unit);
+
+                // Only values with between 2 and N possible squares can be part of a hidden subset
+                let values: Vec<u32> = (1..=9u32)
+                    .filter(|value| {
+                        let spots = self.option_squares(&squares, *value).len();
+                        spots >= 2 && spots <= size
+                    })
+                    .collect();
+
+                for subset in combinations(&values, size) {
+                    let mut spots: Vec<(usize, usize)> = vec![];
+                    for &value in &subset {
+                        for spot in self.option_squares(&squares, value) {
+                            if !spots.contains(&spot) {
+                                spots.push(spot);
+                            }
+                        }
+                    }
+                    if spots.len() != size {
+                        continue;
+                    }
+
+                    // Remove every other option from the subset squares
+                    let mut eliminations: Vec<(usize, usize, u32)> = vec![];
+                    for &(r, c) in &spots {
+                        for &option in self.grid[r][c].options.iter().filter(|option| !subset.contains(option)) {
+                            eliminations.push((r, c, option));
+                        }
+                    }
+
+                    if !eliminations.is_empty() {
+                        let description: String = format!(
+                            "Hidden {}: {} at {} in {}, removed {}",
+                            subset_name(size),
+                            vec_to_string(&subset),
+                            squares_to_string(&spots),
+                            unit_name(unit),
+                            removed_to_string(&eliminations)
+                        );
+                        let step = SolveStep { eliminations, description, ..SolveStep::default() };
+                        return Some(step.involving(&spots).rated(subset_difficulty(size, true)));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Combine the options of several squares, listing each value once in order
+    fn combined_options(&self, squares: &[(usize, usize)]) -> Vec<u32> {
+        (1..=9u32)
+            .filter(|value| squares.iter().any(|&(r, c)| self.grid[r][c].options.contains(value)))
+            .collect()
+    }
+}
+
+// Helper function to print out removed options, e.g. "3 from R1C5, 7 from R1C5"
+fn removed_to_string(eliminations: &[(usize, usize, u32)]) -> String {
+    eliminations
+        .iter()
+        .map(|&(r, c, value)| format!("{} from {}", value, square_name(r, c)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// How hard a subset of the size is to spot, with hidden subsets harder than naked ones
+fn subset_difficulty(size: usize, hidden: bool) -> f32 {
+    match (size, hidden) {
+        (2, false) => 3.0,
+        (2, true) => 3.4,
+        (3, false) => 3.6,
+        (3, true) => 4.0,
+        (_, false) => 5.0,
+        (_, true) => 5.4,
+    }
+}
+
+// Name a subset size for display
+fn subset_name(size: usize) -> &'static str {
+    match size {
+        2 => "pair",
+        3 => "triple",
+        _ => "quad",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_a_naked_pair() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "9   7   1  | 8  3   5  | 2  6   4
+             38  36  68 | 1  4   2  | 7  5   9
+             2   4   5  | 6  7   9  | 8  1   3
+             4   8   9  | 5  2   3  | 6  7   1
+             6   5   3  | 7  8   1  | 4  9   2
+             7   1   2  | 4  9   6  | 3  8   5
+             1   36  7  | 2  56  48 | 9  34  68
+             58  9   4  | 3  56  7  | 1  2   68
+             38  2   68 | 9  1   48 | 5  34  7",
+        )
+        .unwrap();
+        assert_step(sudoku.find_naked_subset(), "Naked pair", None, &[(7, 0, 8)]);
+    }
+
+    #[test]
+    fn finds_a_naked_triple() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "8    4   6  | 2   7   3  | 1  5  9
+             57   57  9  | 8   1   4  | 3  2  6
+             3    1   2  | 5   69  69 | 4  8  7
+             9    8   37 | 67  36  1  | 5  4  2
+             47   2   1  | 47  5   8  | 6  9  3
+             456  56  34 | 49  39  2  | 7  1  8
+             1    36  8  | 36  2   5  | 9  7  4
+             467  9   47 | 1   8   67 | 2  3  5
+             2    37  5  | 39  4   79 | 8  6  1",
+        )
+        .unwrap();
+        assert_step(sudoku.find_naked_subset(), "Naked triple", None, &[(5, 0, 4)]);
+    }
+
+    #[test]
+    fn finds_a_hidden_pair() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "6  3  1 | 8  29  5  | 4   279  79
+             5  8  7 | 6  4   29 | 1   39   239
+             4  2  9 | 7  3   1  | 8   6    5
+             2  7  5 | 9  1   3  | 6   8    4
+             8  9  4 | 5  7   6  | 23  1    23
+             3  1  6 | 2  8   4  | 79  5    79
+             9  4  2 | 1  5   8  | 37  37   6
+             7  5  8 | 3  6   29 | 29  4    1
+             1  6  3 | 4  29  7  | 5   29   8",
+        )
+        .unwrap();
+        assert_step(sudoku.find_hidden_subset(), "Hidden pair", None, &[(1, 8, 9)]);
+    }
+
+    #[test]
+    fn finds_a_hidden_triple() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "2    67  567 | 57  9  1    | 3  4  8
+             4    9   35  | 35  8  6    | 7  2  1
+             1    8   37  | 37  4  2    | 5  6  9
+             6    5   89  | 89  7  3    | 4  1  2
+             89   4   2   | 1   5  89   | 6  7  3
+             7    3   1   | 2   6  4    | 8  9  5
+             3    1   678 | 4   2  78   | 9  5  67
+             59   2   79  | 6   3  579  | 1  8  4
+             589  67  4   | 89  1  5789 | 2  3  67",
+        )
+        .unwrap();
+        assert_step(sudoku.find_hidden_subset(), "Hidden triple", None, &[(8, 5, 7)]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use super::{Coloring, Sudoku};
+
+// A single deduction made while solving, kept as the solution path. Techniques find it without
+// changing the grid, and the solver fills in the technique name when it applies the step
+#[derive(Debug, Clone, Default)]
+pub struct SolveStep {
+    // The name of the technique which found the step
+    pub technique: &'static str,
+    // The value placed by the step, as (row, column, value)
+    pub placement: Option<(usize, usize, u32)>,
+    // The options removed by the step, as (row, column, value)
+    pub eliminations: Vec<(usize, usize, u32)>,
+    // The squares making up the pattern which the step is based on
+    pub squares: Vec<(usize, usize)>,
+    // How hard the step is to spot, which is the technique's difficulty unless the pattern is harder
+    pub difficulty: f32,
+    pub description: String,
+    // The color classes of a coloring step, to show in the options grid
+    pub coloring: Option<Coloring>,
+}
+
+impl SolveStep {
+    // A step which places one value
+    pub fn place(r: usize, c: usize, value: u32, description: String) -> SolveStep {
+        SolveStep { placement: Some((r, c, value)), squares: vec![(r, c)], description, ..SolveStep::default() }
+    }
+
+    // A step which removes one value from several squares
+    pub fn remove(value: u32, squares: &[(usize, usize)], description: String) -> SolveStep {
+        let eliminations = squares.iter().map(|&(r, c)| (r, c, value)).collect();
+        SolveStep { eliminations, description, ..SolveStep::default() }
+    }
+
+    // Set the squares making up the pattern, listing each once
+    pub fn involving(mut self, squares: &[(usize, usize)]) -> SolveStep {
+        self.squares = vec![];
+        for &square in squares {
+            if !self.squares.contains(&square) {
+                self.squares.push(square);
+            }
+        }
+        self
+    }
+
+    // Set the difficulty of a pattern which is harder than the technique's usual difficulty
+    pub fn rated(mut self, difficulty: f32) -> SolveStep {
+        self.difficulty = difficulty;
+        self
+    }
+}
+
+// A way of finding the next step of a solve. The solver tries each technique in its settings in
+// order, so new ones can be added to the list without changing the solve loop
+pub trait Technique: Debug + Send + Sync {
+    // The name shown to the user when enabling or disabling the technique
+    fn name(&self) -> &'static str;
+
+    // How hard the technique is for a person to spot, roughly on the Sudoku Explainer scale
+    fn difficulty(&self) -> f32;
+
+    // Look for a step in the current options, without changing the grid
+    fn find_step(&self, sudoku: &Sudoku) -> Option<SolveStep>;
+}
+
+// Look for a step using the technique, filling in the technique's name, and its difficulty if the
+// step doesn't have its own
+pub fn find_step_with(technique: &dyn Technique, sudoku: &Sudoku) -> Option<SolveStep> {
+    let mut step: SolveStep = technique.find_step(sudoku)?;
+    step.technique = technique.name();
+    if step.difficulty < technique.difficulty() {
+        step.difficulty = technique.difficulty();
+    }
+    Some(step)
+}
+
+// One of the techniques which come with the solver
+#[derive(Debug)]
+struct BuiltinTechnique {
+    name: &'static str,
+    difficulty: f32,
+    find: fn(&Sudoku) -> Option<SolveStep>,
+}
+
+impl Technique for BuiltinTechnique {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn difficulty(&self) -> f32 {
+        self.difficulty
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<SolveStep> {
+        (self.find)(sudoku)
+    }
+}
+
+// Names of the built-in techniques which place values directly from the options
+pub const SINGLES: [&str; 2] = ["Naked Single", "Hidden Single"];
+
+// List the built-in techniques in the order the solver tries them
+pub fn default_techniques() -> Vec<Arc<dyn Technique>> {
+    vec![
+        builtin("Naked Single", 2.3, Sudoku::find_naked_single),
+        builtin("Hidden Single", 1.2, Sudoku::find_hidden_single),
+        builtin("Pointing", 2.6, Sudoku::find_pointing),
+        builtin("Claiming", 2.8, Sudoku::find_claiming),
+        builtin("Naked Subset", 3.0, Sudoku::find_naked_subset),
+        builtin("Hidden Subset", 3.4, Sudoku::find_hidden_subset),
+        builtin("Basic Fish", 3.2, Sudoku::find_basic_fish),
+        builtin("Finned Fish", 3.4, Sudoku::find_finned_fish),
+        builtin("Wings", 4.2, Sudoku::find_wing),
+        builtin("Single Digit Patterns", 4.4, Sudoku::find_single_digit_pattern),
+        builtin("Coloring", 5.0, Sudoku::coloring_step),
+        builtin("Uniqueness", 4.5, Sudoku::find_uniqueness),
+        builtin("Chains", 6.5, Sudoku::find_chain),
+        builtin("Sue de Coq", 5.0, Sudoku::find_sue_de_coq),
+        builtin("Almost Locked Sets", 7.0, Sudoku::find_als),
+        builtin("Forcing Chains", 7.5, Sudoku::find_forcing),
+    ]
+}
+
+// Shorthand for creating a built-in technique
+fn builtin(name: &'static str, difficulty: f32, find: fn(&Sudoku) -> Option<SolveStep>) -> Arc<dyn Technique> {
+    Arc::new(BuiltinTechnique { name, difficulty, find })
+}
+
+// Check that the technique found a step with the name and exactly this placement and these eliminations
+#[cfg(test)]
+pub fn assert_step(
+    step: Option<SolveStep>,
+    name: &str,
+    placement: Option<(usize, usize, u32)>,
+    eliminations: &[(usize, usize, u32)],
+) {
+    let step: SolveStep = step.unwrap_or_else(|| panic!("no {} found", name));
+    assert!(step.description.starts_with(&format!("{}:", name)), "{}", step.description);
+    assert_eq!(step.placement, placement);
+    assert_eq!(step.eliminations, eliminations);
+}
@@ -0,0 +1,391 @@
+use super::{square_name, squares_to_string, unit_squares, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Check whether the current grid has exactly one solution, remembering the answer until it is edited
+    fn has_unique_solution(&self) -> bool {
+        if self.unique.get().is_none() {
+            self.unique.set(Some(self.count_solutions(2) == 1));
+        }
+        self.unique.get() == Some(true)
+    }
+
+    // Find the first BUG+1 or Unique Rectangle which makes progress. These rely on the puzzle having
+    // a single solution, so they are skipped unless that is known or assumed
+    pub fn find_uniqueness(&self) -> Option<SolveStep> {
+        if !self.settings.assume_unique && !self.has_unique_solution() {
+            return None;
+        }
+        if let Some(step) = self.find_bug_plus_one() {
+            return Some(step);
+        }
+
+        for r1 in 0..9usize {
+            for r2 in r1 + 1..9 {
+                for c1 in 0..9usize {
+                    for c2 in c1 + 1..9 {
+                        // A deadly rectangle covers exactly two boxes
+                        if (r1 / 3 == r2 / 3) == (c1 / 3 == c2 / 3) {
+                            continue;
+                        }
+                        let corners: [(usize, usize); 4] = [(r1, c1), (r1, c2), (r2, c1), (r2, c2)];
+                        if corners.iter().any(|&(r, c)| self.grid[r][c].value.is_some()) {
+                            continue;
+                        }
+
+                        for a in 1..=9u32 {
+                            for b in a + 1..=9 {
+                                let has_pair = |&(r, c): &(usize, usize)| {
+                                    self.grid[r][c].options.contains(&a) && self.grid[r][c].options.contains(&b)
+                                };
+                                if !corners.iter().all(has_pair) {
+                                    continue;
+                                }
+                                if let Some(step) = self.find_unique_rectangle(corners, a, b) {
+                                    return Some(step);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Check each type of Unique Rectangle on four corners which all have the options A and B.
+    // If the corners could be left with only A and B, the two values could be swapped to give a
+    // second solution, so at least one corner must end up with something else
+    fn find_unique_rectangle(&self, corners: [(usize, usize); 4], a: u32, b: u32) -> Option<SolveStep> {
+        let extras = |(r, c): (usize, usize)| -> Vec<u32> {
+            self.grid[r][c].options.iter().filter(|&&x| x != a && x != b).copied().collect()
+        };
+        let floor: Vec<(usize, usize)> = corners.iter().filter(|&&sq| extras(sq).is_empty()).copied().collect();
+        let roof: Vec<(usize, usize)> = corners.iter().filter(|&&sq| !extras(sq).is_empty()).copied().collect();
+        // Describe the deduction, e.g. "Unique Rectangle Type 1: 37 at R1C1, R1C4, R2C1, R2C4, removed 3R2C4, 7R2C4"
+        let step = |name: &str, eliminations: Vec<(usize, usize, u32)>| -> Option<SolveStep> {
+            if eliminations.is_empty() {
+                return None;
+            }
+            let removed: Vec<String> =
+                eliminations.iter().map(|&(r, c, value)| format!("{}{}", value, square_name(r, c))).collect();
+            let description: String =
+                format!("{}: {}{} at {}, removed {}", name, a, b, squares_to_string(&corners), removed.join(", "));
+            Some(SolveStep { eliminations, description, ..SolveStep::default() }.involving(&corners))
+        };
+
+        // Type 1: only one corner has other options, so it can't be A or B
+        if roof.len() == 1 {
+            let (r, c) = roof[0];
+            return step("Unique Rectangle Type 1", vec![(r, c, a), (r, c, b)]);
+        }
+
+        // Type 2 and 5: the corners with other options all have the same single extra X, so one of
+        // them must be X and it is removed from the squares seeing all of them
+        let roof_extras: Vec<Vec<u32>> = roof.iter().map(|&sq| extras(sq)).collect();
+        if roof.len() >= 2 && roof_extras.iter().all(|extra| extra.len() == 1 && extra[0] == roof_extras[0][0]) {
+            let x: u32 = roof_extras[0][0];
+            let side: bool = roof.len() == 2 && (roof[0].0 == roof[1].0 || roof[0].1 == roof[1].1);
+            let name = if side { "Unique Rectangle Type 2" } else { "Unique Rectangle Type 5" };
+            let eliminations = self.squares_seeing_all(&roof, x).into_iter().map(|(r, c)| (r, c, x)).collect();
+            if let Some(found) = step(name, eliminations) {
+                return Some(found);
+            }
+        }
+
+        if roof.len() != 2 {
+            return None;
+        }
+        let (p, q) = (roof[0], roof[1]);
+        let shared_units: Vec<usize> = [p.0, 9 + p.1, 18 + (p.0 / 3) * 3 + p.1 / 3]
+            .into_iter()
+            .filter(|&unit| unit_squares(unit).contains(&q))
+            .collect();
+
+        for &unit in &shared_units {
+            // Type 3: the extras of the two roof corners act as one square, forming a naked subset
+            // with other squares of the unit they share
+            let mut virtual_options: Vec<u32> = roof_extras.concat();
+            virtual_options.sort();
+            virtual_options.dedup();
+            let others: Vec<(usize, usize)> = unit_squares(unit)
+                .into_iter()
+                .filter(|&(r, c)| self.grid[r][c].value.is_none() && !roof.contains(&(r, c)))
+                .collect();
+            for size in 1..=3usize {
+                for subset in super::combinations(&others, size) {
+                    let mut values: Vec<u32> = virtual_options.clone();
+                    for &(r, c) in &subset {
+                        values.extend(self.grid[r][c].options.iter());
+                    }
+                    values.sort();
+                    values.dedup();
+                    if values.len() != size + 1 {
+                        continue;
+                    }
+                    let mut eliminations: Vec<(usize, usize, u32)> = vec![];
+                    for &(r, c) in others.iter().filter(|sq| !subset.contains(sq)) {
+                        for &value in &values {
+                            if self.grid[r][c].options.contains(&value) {
+                                eliminations.push((r, c, value));
+                            }
+                        }
+                    }
+                    if let Some(found) = step("Unique Rectangle Type 3", eliminations) {
+                        return Some(found);
+                    }
+                }
+            }
+
+            // Type 4: one of the pair can only go in the roof corners within their shared unit, so
+            // the roof holds that value and the other one of the pair is removed from both
+            for (v, w) in [(a, b), (b, a)] {
+                let spots = self.option_squares(&unit_squares(unit), v);
+                if spots.len() == 2 && spots.contains(&p) && spots.contains(&q) {
+                    if let Some(found) = step("Unique Rectangle Type 4", vec![(p.0, p.1, w), (q.0, q.1, w)]) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+
+        // Type 6: the roof corners are diagonal, and one of the pair is only in the rectangle in
+        // both of its rows (or columns), so putting it in the roof would leave the deadly pattern
+        if shared_units.is_empty() {
+            let rows: [usize; 2] = [corners[0].0, corners[3].0];
+            let cols: [usize; 2] = [corners[0].1, corners[3].1];
+            for v in [a, b] {
+                let in_rectangle = |unit: usize| {
+                    self.option_squares(&unit_squares(unit), v).iter().all(|sq| corners.contains(sq))
+                };
+                if (in_rectangle(rows[0]) && in_rectangle(rows[1])) || (in_rectangle(9 + cols[0]) && in_rectangle(9 + cols[1])) {
+                    if let Some(found) = step("Unique Rectangle Type 6", vec![(p.0, p.1, v), (q.0, q.1, v)]) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+
+        // Hidden Unique Rectangle: a corner with only A and B, where A can only go in the rectangle
+        // along both the row and column of the opposite corner. If the opposite corner were B then
+        // A would fill the other two corners and the first corner would be B, so it can't be B
+        for &(r, c) in &floor {
+            let opposite: (usize, usize) = corners[3 - corners.iter().position(|&sq| sq == (r, c)).unwrap()];
+            for (v, w) in [(a, b), (b, a)] {
+                let row_spots = self.option_squares(&unit_squares(opposite.0), v);
+                let col_spots = self.option_squares(&unit_squares(9 + opposite.1), v);
+                if row_spots.len() == 2 && col_spots.len() == 2
+                    && row_spots.iter().chain(col_spots.iter()).all(|sq| corners.contains(sq))
+                {
+                    if let Some(found) = step("Hidden Unique Rectangle", vec![(opposite.0, opposite.1, w)]) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // BUG+1: every empty square has two options except one with three. Without the extra value
+    // every option would appear exactly twice in each unit and the grid would have two solutions,
+    // so the square must take the value which appears three times in its units
+    fn find_bug_plus_one(&self) -> Option<SolveStep> {
+        let mut triple: Option<(usize, usize)> = None;
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_some() {
+                    continue;
+                }
+                match self.grid[r][c].options.len() {
+                    2 => {}
+                    3 if triple.is_none() => triple = Some((r, c)),
+                    _ => return None,
+                }
+            }
+        }
+        let (r, c) = triple?;
+
+        // The extra value is the one found three times in the square's row
+        let value: u32 = *self.grid[r][c]
+            .options
+            .iter()
+            .find(|&&value| self.option_squares(&unit_squares(r), value).len() == 3)?;
+
+        // Without the extra value, every other option must appear exactly twice in each unit
+        for unit in 0..27 {
+            for option in 1..=9u32 {
+                let count: usize = self
+                    .option_squares(&unit_squares(unit), option)
+                    .iter()
+                    .filter(|&&sq| !(sq == (r, c) && option == value))
+                    .count();
+                if count != 0 && count != 2 {
+                    return None;
+                }
+            }
+        }
+
+        let square: String = square_name(r, c);
+        Some(SolveStep::place(r, c, value, format!("BUG+1: {} at {}, placed {} at {}", value, square, value, square)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::empty_board;
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_unique_rectangle_type_1() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "46   1  46 | 8   5    7 | 24  3   9
+             469  8  3  | 26  26   1 | 5   24  7
+             7    5  2  | 3   9    4 | 6   1   8
+             2    4  7  | 9   3    6 | 1   8   5
+             5    3  1  | 4   7    8 | 9   6   2
+             8    6  9  | 5   1    2 | 3   7   4
+             3    7  46 | 26  268  9 | 24  5   1
+             46   9  8  | 1   26   5 | 7   24  3
+             1    2  5  | 7   4    3 | 8   9   6",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "Unique Rectangle Type 1", None, &[(6, 4, 2), (6, 4, 6)]);
+    }
+
+    #[test]
+    fn finds_unique_rectangle_type_2() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "8  6  3 | 57   9    2 | 15  17   4
+             9  2  1 | 567  367  4 | 8   367  36
+             7  5  4 | 1    36   8 | 2   36   369
+             3  4  5 | 9    2    7 | 16  8    16
+             6  7  2 | 8    4    1 | 3   9    5
+             1  8  9 | 3    5    6 | 4   2    7
+             2  1  8 | 67   67   5 | 9   4    36
+             5  3  7 | 4    8    9 | 16  16   126
+             4  9  6 | 2    1    3 | 7   5    8",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "Unique Rectangle Type 2", None, &[(1, 3, 7)]);
+    }
+
+    #[test]
+    fn finds_unique_rectangle_type_3() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "2   1   8   | 456  46   56 | 7  9   3
+             3   6   7   | 2    1    9  | 5  4   8
+             4   5   9   | 8    7    3  | 6  2   1
+             16  4   136 | 36   2    7  | 8  5   9
+             9   7   5   | 1    8    4  | 3  6   2
+             68  38  2   | 356  9    56 | 4  1   7
+             68  9   346 | 7    346  2  | 1  38  5
+             7   38  36  | 9    5    1  | 2  38  4
+             15  2   134 | 34   34   8  | 9  37  6",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "Unique Rectangle Type 3", None, &[(3, 3, 3)]);
+    }
+
+    #[test]
+    fn finds_unique_rectangle_type_4() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5   4   9  | 2  1  3 | 6    7   8
+             6   13  8  | 5  9  7 | 134  12  124
+             17  2   37 | 6  8  4 | 13   9   5
+             3   7   5  | 8  2  9 | 14   6   14
+             4   8   1  | 7  6  5 | 2    3   9
+             9   6   2  | 4  3  1 | 8    5   7
+             8   13  37 | 9  4  6 | 5    12  12
+             17  9   4  | 3  5  2 | 17   8   6
+             2   5   6  | 1  7  8 | 9    4   3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "Unique Rectangle Type 4", None, &[(1, 6, 1), (1, 8, 1)]);
+    }
+
+    // Type 5 needs the roof corners on a diagonal with one extra option each, so it is set up by hand
+    // on a board where every other square has every option
+    fn type_5_rectangle() -> Sudoku {
+        let mut sudoku: Sudoku = Sudoku::new(empty_board());
+        sudoku.update_all_options();
+        for (r, c) in [(0, 0), (1, 3)] {
+            sudoku.grid[r][c].options = vec![1, 2];
+        }
+        for (r, c) in [(0, 3), (1, 0)] {
+            sudoku.grid[r][c].options = vec![1, 2, 3];
+        }
+        sudoku
+    }
+
+    #[test]
+    fn finds_unique_rectangle_type_5() {
+        let mut sudoku: Sudoku = type_5_rectangle();
+        sudoku.settings.assume_unique = true;
+        assert_step(
+            sudoku.find_uniqueness(),
+            "Unique Rectangle Type 5",
+            None,
+            &[(0, 1, 3), (0, 2, 3), (1, 4, 3), (1, 5, 3)],
+        );
+    }
+
+    #[test]
+    fn finds_unique_rectangle_type_6() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "2   4    6 | 7   9    1 | 5  3   8
+             18  7    9 | 25  58   3 | 4  6   12
+             3   18   5 | 24  48   6 | 7  19  129
+             6   39   7 | 35  1    8 | 2  59  4
+             5   239  8 | 6   23   4 | 1  7   39
+             4   23   1 | 9   235  7 | 6  8   35
+             18  5    3 | 18  6    2 | 9  4   7
+             9   18   4 | 18  7    5 | 3  2   6
+             7   6    2 | 34  34   9 | 8  15  15",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "Unique Rectangle Type 6", None, &[(4, 1, 2), (5, 4, 2)]);
+    }
+
+    #[test]
+    fn finds_a_hidden_unique_rectangle() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "1   5  6 | 7   4   9   | 28    3   28
+             2   7  4 | 13  5   38  | 168   9   68
+             3   8  9 | 2   16  16  | 5     14  7
+             5   4  2 | 8   3   7   | 9     6   1
+             7   1  8 | 9   26  246 | 3     24  5
+             9   6  3 | 5   12  124 | 7     8   24
+             8   9  5 | 6   7   123 | 4     12  23
+             46  3  7 | 14  28  248 | 1268  5   9
+             46  2  1 | 34  9   5   | 68    7   368",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "Hidden Unique Rectangle", None, &[(1, 8, 8)]);
+    }
+
+    #[test]
+    fn finds_a_bug_plus_one() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_uniqueness(), "BUG+1", Some((5, 0, 9)), &[]);
+    }
+
+    #[test]
+    fn needs_a_unique_solution_unless_assumed() {
+        // The empty board has many solutions, so the rectangle is only used once uniqueness is assumed
+        let mut sudoku: Sudoku = type_5_rectangle();
+        assert!(sudoku.find_uniqueness().is_none());
+        sudoku.settings.assume_unique = true;
+        assert!(sudoku.find_uniqueness().is_some());
+    }
+}
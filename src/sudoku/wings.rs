@@ -0,0 +1,204 @@
+use super::{sees, squares_to_string, unit_squares, SolveStep, Sudoku};
+
+impl Sudoku {
+    // Find the first XY-Wing, XYZ-Wing, or W-Wing which removes at least one option
+    pub fn find_wing(&self) -> Option<SolveStep> {
+        self.find_xy_wing()
+            .or_else(|| self.find_xyz_wing())
+            .or_else(|| self.find_w_wing())
+    }
+
+    // XY-Wing: a pivot with options XY sees one pincer with XZ and another with YZ. Whichever
+    // value the pivot takes, one of the pincers must be Z, so Z is removed from squares seeing both
+    fn find_xy_wing(&self) -> Option<SolveStep> {
+        let bivalue: Vec<(usize, usize)> = self.squares_with_options(2);
+        for &pivot in &bivalue {
+            let pivot_options: &Vec<u32> = &self.grid[pivot.0][pivot.1].options;
+            for &a in bivalue.iter().filter(|&&a| sees(pivot, a)) {
+                // The first pincer shares exactly one value X with the pivot
+                let a_options: &Vec<u32> = &self.grid[a.0][a.1].options;
+                let shared: Vec<u32> = a_options.iter().filter(|x| pivot_options.contains(x)).copied().collect();
+                if shared.len() != 1 {
+                    continue;
+                }
+                let z: u32 = *a_options.iter().find(|&&x| x != shared[0]).unwrap();
+                let y: u32 = *pivot_options.iter().find(|&&x| x != shared[0]).unwrap();
+
+                // The second pincer holds the other pivot value Y and the same Z
+                for &b in bivalue.iter().filter(|&&b| b != a && sees(pivot, b)) {
+                    let b_options: &Vec<u32> = &self.grid[b.0][b.1].options;
+                    if !(b_options.contains(&y) && b_options.contains(&z)) {
+                        continue;
+                    }
+                    let eliminations = self.squares_seeing_all(&[a, b], z);
+                    if !eliminations.is_empty() {
+                        return Some(wing_step("XY-Wing", &[pivot], &[a, b], z, &eliminations));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // XYZ-Wing: a pivot with options XYZ sees one pincer with XZ and another with YZ. One of
+    // the three squares must be Z, so Z is removed from squares seeing all three
+    fn find_xyz_wing(&self) -> Option<SolveStep> {
+        let bivalue: Vec<(usize, usize)> = self.squares_with_options(2);
+        for pivot in self.squares_with_options(3) {
+            let pivot_options: &Vec<u32> = &self.grid[pivot.0][pivot.1].options;
+            let pincers: Vec<(usize, usize)> = bivalue
+                .iter()
+                .filter(|&&a| sees(pivot, a) && self.grid[a.0][a.1].options.iter().all(|x| pivot_options.contains(x)))
+                .copied()
+                .collect();
+
+            for (i, &a) in pincers.iter().enumerate() {
+                for &b in &pincers[i + 1..] {
+                    // The pincers share only Z, and between them cover all three pivot values
+                    let a_options: &Vec<u32> = &self.grid[a.0][a.1].options;
+                    let b_options: &Vec<u32> = &self.grid[b.0][b.1].options;
+                    let shared: Vec<u32> = a_options.iter().filter(|x| b_options.contains(x)).copied().collect();
+                    if shared.len() != 1 {
+                        continue;
+                    }
+                    let z: u32 = shared[0];
+                    let eliminations = self.squares_seeing_all(&[pivot, a, b], z);
+                    if !eliminations.is_empty() {
+                        return Some(wing_step("XYZ-Wing", &[pivot], &[a, b], z, &eliminations));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // W-Wing: two pincers with the same options XY which are joined by a strong link on X (a unit
+    // where X has only two squares, one seeing each pincer). One of the pincers must be Y, so Y is
+    // removed from squares seeing both
+    fn find_w_wing(&self) -> Option<SolveStep> {
+        let bivalue: Vec<(usize, usize)> = self.squares_with_options(2);
+        for (i, &a) in bivalue.iter().enumerate() {
+            for &b in &bivalue[i + 1..] {
+                let options: &Vec<u32> = &self.grid[a.0][a.1].options;
+                if *options != self.grid[b.0][b.1].options || sees(a, b) {
+                    continue;
+                }
+
+                for (x, y) in [(options[0], options[1]), (options[1], options[0])] {
+                    for unit in 0..27 {
+                        let link: Vec<(usize, usize)> = self.option_squares(&unit_squares(unit), x);
+                        if link.len() != 2 || link.contains(&a) || link.contains(&b) {
+                            continue;
+                        }
+
+                        // Order the link ends so the first sees pincer A and the second sees pincer B
+                        let (l1, l2) = if sees(link[0], a) && sees(link[1], b) {
+                            (link[0], link[1])
+                        } else if sees(link[1], a) && sees(link[0], b) {
+                            (link[1], link[0])
+                        } else {
+                            continue;
+                        };
+
+                        let eliminations = self.squares_seeing_all(&[a, b], y);
+                        if !eliminations.is_empty() {
+                            return Some(wing_step("W-Wing", &[l1, l2], &[a, b], y, &eliminations));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // List the empty squares with exactly the given number of options
+    fn squares_with_options(&self, count: usize) -> Vec<(usize, usize)> {
+        let mut squares: Vec<(usize, usize)> = vec![];
+        for r in 0..9 {
+            for c in 0..9 {
+                if self.grid[r][c].value.is_none() && self.grid[r][c].options.len() == count {
+                    squares.push((r, c));
+                }
+            }
+        }
+        squares
+    }
+}
+
+// Build the step for a wing, e.g. "XY-Wing: pivot R1C1, pincers R1C5, R3C2, removed 7 from R3C4"
+// The pivot is one square, or for a W-Wing the two ends of the strong link joining the pincers
+fn wing_step(
+    name: &str,
+    pivot: &[(usize, usize)],
+    pincers: &[(usize, usize)],
+    value: u32,
+    eliminations: &[(usize, usize)],
+) -> SolveStep {
+    let description: String = format!(
+        "{}: pivot {}, pincers {}, removed {} from {}",
+        name,
+        squares_to_string(pivot),
+        squares_to_string(pincers),
+        value,
+        squares_to_string(eliminations)
+    );
+    let difficulty: f32 = if name == "XY-Wing" { 4.2 } else { 4.4 };
+    SolveStep::remove(value, eliminations, description).involving(&[pivot, pincers].concat()).rated(difficulty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::technique::assert_step;
+    use super::*;
+
+    #[test]
+    fn finds_an_xy_wing() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "9   7   1  | 8  3   5  | 2  6   4
+             38  36  68 | 1  4   2  | 7  5   9
+             2   4   5  | 6  7   9  | 8  1   3
+             4   8   9  | 5  2   3  | 6  7   1
+             6   5   3  | 7  8   1  | 4  9   2
+             7   1   2  | 4  9   6  | 3  8   5
+             1   36  7  | 2  56  48 | 9  34  68
+             58  9   4  | 3  56  7  | 1  2   68
+             38  2   68 | 9  1   48 | 5  34  7",
+        )
+        .unwrap();
+        assert_step(sudoku.find_wing(), "XY-Wing", None, &[(7, 0, 8), (8, 5, 8)]);
+    }
+
+    #[test]
+    fn finds_an_xyz_wing() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5   4   9  | 2  1  3 | 6    7   8
+             6   13  8  | 5  9  7 | 134  12  124
+             17  2   37 | 6  8  4 | 13   9   5
+             3   7   5  | 8  2  9 | 14   6   14
+             4   8   1  | 7  6  5 | 2    3   9
+             9   6   2  | 4  3  1 | 8    5   7
+             8   13  37 | 9  4  6 | 5    12  12
+             17  9   4  | 3  5  2 | 17   8   6
+             2   5   6  | 1  7  8 | 9    4   3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_wing(), "XYZ-Wing", None, &[(7, 6, 1)]);
+    }
+
+    #[test]
+    fn finds_a_w_wing() {
+        let sudoku: Sudoku = Sudoku::from_pencil_marks(
+            "5    2   9 | 1   8  3  | 6  4  7
+             8    1   6 | 4   9  7  | 3  2  5
+             4    7   3 | 5   6  2  | 1  9  8
+             3    4   7 | 8   2  16 | 9  5  16
+             19   8   2 | 69  4  5  | 7  3  16
+             169  69  5 | 3   7  19 | 4  8  2
+             7    3   4 | 2   1  8  | 5  6  9
+             69   5   8 | 7   3  69 | 2  1  4
+             2    69  1 | 69  5  4  | 8  7  3",
+        )
+        .unwrap();
+        assert_step(sudoku.find_wing(), "W-Wing", None, &[(5, 0, 1)]);
+    }
+}